@@ -0,0 +1,115 @@
+//! Times the broad phase hit detection between the bullets and the pandas on random gameboards,
+//! against the brute-force version of `collisions_reference` testing every pair, and checks that
+//! both find the same hits on each of them.
+//!
+//! ```bash
+//! cargo bench --bench collisions
+//! ```
+
+// Only the collision detection and what it relies on is used out of the game modules.
+#![allow(dead_code)]
+
+extern crate fnv;
+extern crate ggez;
+extern crate nalgebra as na;
+extern crate ncollide2d;
+extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate toml;
+
+#[path = "../src/actors/mod.rs"]
+mod actors;
+#[path = "../src/arena.rs"]
+mod arena;
+#[path = "../src/collisions.rs"]
+mod collisions;
+#[path = "../src/collisions_reference.rs"]
+mod collisions_reference;
+#[path = "../src/color_picker.rs"]
+mod color_picker;
+#[path = "../src/config.rs"]
+mod config;
+#[path = "../src/gameboard_controller.rs"]
+mod gameboard_controller;
+#[path = "../src/obstacles.rs"]
+mod obstacles;
+#[path = "../src/sprites.rs"]
+mod sprites;
+
+use arena::Arena;
+use collisions_reference::{
+    brute_force_hits, hit_pairs, random_board, BULLET_NUMBER, PANDA_NUMBER,
+};
+use config::ActorConfig;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Instant;
+
+// The time step of a tick in the game, see `gameboard::TICK_DT`.
+const DT: f32 = 1.0 / 180.0;
+// Number of random gameboards timed.
+const ROUNDS: usize = 100;
+// The seed the random gameboards are drawn from.
+const SEED: u64 = 42;
+
+/// Returns the time elapsed since `start` in milliseconds.
+fn elapsed_ms(start: Instant) -> f64 {
+    let elapsed = start.elapsed();
+    (elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) * 1e-9) * 1_000.0
+}
+
+/// Times both versions on the same random gameboards.
+fn bench(arena: &Arena, config: &ActorConfig, torus_size: Option<(f32, f32)>) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut brute_force_ms: f64 = 0.0;
+    let mut broad_phase_ms: f64 = 0.0;
+    let mut hit_number = 0;
+
+    for round in 0..ROUNDS {
+        let (panda_vector, bullet_vector) = random_board(&mut rng, arena, config, DT);
+
+        let start = Instant::now();
+        let brute_force = brute_force_hits(&panda_vector, &bullet_vector, torus_size);
+        brute_force_ms += elapsed_ms(start);
+
+        let start = Instant::now();
+        let broad_phase = collisions::broad_phase_hits(
+            &panda_vector,
+            &bullet_vector,
+            torus_size,
+            config.wrap_margin(),
+        );
+        broad_phase_ms += elapsed_ms(start);
+
+        // The hits are used, so neither search can be optimised away.
+        hit_number += broad_phase.len();
+        assert_eq!(
+            hit_pairs(broad_phase),
+            hit_pairs(brute_force),
+            "Round {}, torus {:?}",
+            round,
+            torus_size
+        );
+    }
+
+    println!(
+        "{} rounds of {} pandas and {} bullets, {} world:",
+        ROUNDS,
+        PANDA_NUMBER,
+        BULLET_NUMBER,
+        if torus_size.is_some() { "wrapped" } else { "confined" }
+    );
+    println!("Hits found  : {:>10}", hit_number);
+    println!("Brute force : {:>10.3} ms", brute_force_ms);
+    println!("Broad phase : {:>10.3} ms", broad_phase_ms);
+}
+
+fn main() {
+    let arena = Arena::default();
+    let config = ActorConfig::default();
+
+    bench(&arena, &config, None);
+    bench(&arena, &config, Some(arena.torus_size(config.wrap_margin())));
+}
@@ -31,13 +31,28 @@ pub struct Bullet {
     radius: f32,
     // The color of the panda that fired this bullet.
     color: [f32; 4],
+    // The distance travelled since it was fired.
+    travelled: f32,
+    // Time in second since it was fired.
+    age: f32,
 }
 
 impl Bullet {
-    pub fn new(tag: usize, pos: Point2, facing: f32, color: &[f32; 4]) -> Self {
-        let thrust_vector: Vector2 = vec_from_angle(facing) * ACTOR_THRUST;
-        let nshape = Ball::new(BULLET_RADIUS);
+    pub fn new(
+        tag: usize,
+        team: Option<usize>,
+        pos: Point2,
+        facing: f32,
+        color: &[f32; 4],
+        config: &ActorConfig,
+    ) -> Self {
+        let thrust_vector: Vector2 = vec_from_angle(facing) * config.actor_thrust;
+        let nshape = Ball::new(config.bullet_radius());
         let iso = na::Isometry2::new(na::Vector2::new(pos.x, pos.y), na::zero());
+        let mut body = Body::new(tag, BodyKind::Bullet, &nshape, &iso);
+        body.team = team;
+        body.facing = facing;
+        body.color = *color;
 
         Bullet {
             tag,
@@ -46,73 +61,178 @@ impl Bullet {
             facing,
             thrust_vector,
             iso,
-            body: Body::new(tag, true, &nshape, &iso),
+            body,
             nshape,
             velocity: thrust_vector,
             to_remove: false,
-            radius: BULLET_RADIUS,
+            radius: config.bullet_radius(),
             color: *color,
             // color: color.clone(),
+            travelled: 0.0,
+            age: 0.0,
         }
     }
 
     /// Updates the position of a bullet.
-    pub fn update(&mut self, ctx: &mut Context, dt: f32) -> GameResult<()> {
+    pub fn update(
+        &mut self,
+        arena: &Arena,
+        obstacles: &Obstacles,
+        config: &ActorConfig,
+        dt: f32,
+    ) -> GameResult<()> {
         // Clamp the velocity to the max efficiently
         let norm_sq = self.velocity.norm_squared();
-        if norm_sq > MAX_PHYSICS_VEL.powi(2) {
-            self.velocity = self.velocity / norm_sq.sqrt() * MAX_PHYSICS_VEL;
+        if norm_sq > config.max_physics_vel.powi(2) {
+            self.velocity = self.velocity / norm_sq.sqrt() * config.max_physics_vel;
         }
         // self.pos += self.velocity * dt;
-        self.pos += self.velocity * dt * BULLET_SPEED_FACTOR;
+        let mut dv = self.velocity * dt * config.bullet_speed_factor;
+        self.age += dt;
+
+        // A bullet absorbed by an obstacle only travels up to it.
+        let absorbed = self.hit_obstacles(&mut dv, obstacles);
+        self.travelled += dv.norm();
+        self.pos += dv;
+
+        match config.bullet_edge {
+            BulletEdge::Wrap => self.wrap_position(arena, config.wrap_margin()),
+            BulletEdge::Bounce => {
+                // The path of a bullet bouncing off an edge is swept as the straight path of its
+                // mirror image, which ends at the same position.
+                let velocity = self.velocity;
+                self.bounce_off_edges(arena);
+                if self.velocity.x != velocity.x {
+                    dv.x = -dv.x;
+                }
+                if self.velocity.y != velocity.y {
+                    dv.y = -dv.y;
+                }
+            },
+            BulletEdge::Disappear => {},
+        }
 
         self.iso.translation.vector.x = self.pos.x;
         self.iso.translation.vector.y = self.pos.y;
 
         self.body.update(&self.iso);
-
-        // Checks wether we should remove this bullet from the gameboard if it goes out of scope.
-        self.to_remove = !self.in_bbox(ctx);
+        // A wrapped bullet keeps its motion, so it's swept from the other side of the seam.
+        self.body.motion = na::Vector2::new(dv.x, dv.y);
+        self.body.velocity = self.body.motion / dt;
+        self.body.facing = self.facing;
+
+        // Checks wether we should remove this bullet from the gameboard if it goes out of scope,
+        // or if it has flown too far or for too long.
+        let out_of_range = config
+            .bullet_max_range
+            .map_or(false, |range| self.travelled > range);
+        let out_of_time = config
+            .bullet_time_to_live
+            .map_or(false, |time_to_live| self.age > time_to_live);
+        let out_of_arena = config.bullet_edge == BulletEdge::Disappear && !self.in_bbox(arena);
+        self.to_remove = absorbed || out_of_range || out_of_time || out_of_arena;
 
         Ok(())
     }
 
-    /// Draw the bullet on the gameboard.
-    pub fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let mb = &mut graphics::MeshBuilder::new();
+    /// Shortens or reflects the motion of this tick if the bullet hits an obstacle on its way,
+    /// and returns if the bullet was absorbed by it.
+    fn hit_obstacles(&mut self, dv: &mut Vector2, obstacles: &Obstacles) -> bool {
+        if obstacles.is_empty() {
+            return false;
+        }
 
-        mb.circle(graphics::DrawMode::Fill, self.pos, self.radius, 0.1);
+        // The direction of the ray is the whole motion, so the time of impact is a fraction of
+        // the tick.
+        let ray = ncollide2d::query::Ray::new(
+            na::Point2::new(self.pos.x, self.pos.y),
+            na::Vector2::new(dv.x, dv.y),
+        );
+        let intersection = match obstacles.cast_ray(&ray) {
+            Some(ref intersection) if intersection.toi <= 1.0 => intersection.clone(),
+            _ => return false,
+        };
 
-        let mesh: graphics::Mesh = mb
-            .build(ctx)
-            .unwrap_or_else(|_| panic!("Fail to build the bullet mesh with tag {}", self.tag));
-        //     .expect(&format!(
-        //     "Fail to build the bullet mesh with tag {}",
-        //     self.tag
-        // ));
+        let normal = Vector2::new(intersection.normal.x, intersection.normal.y);
+        let normal_norm = normal.norm();
+        // A bullet fired from inside an obstacle can't bounce off it.
+        if obstacles.bullet_impact == BulletImpact::Absorb || normal_norm == 0.0 {
+            *dv *= intersection.toi;
+            return true;
+        }
 
-        let drawparam = graphics::DrawParam {
-            dest: self.pos,
-            rotation: -self.facing as f32,
-            offset: self.pos,
-            // TODO: a bullet should have the same color as the panda that fired it.
-            color: Some(self.color.into()),
-            ..graphics::DrawParam::default()
-        };
+        // The rest of the motion after the impact, and the velocity, are mirrored on the surface
+        // of the obstacle.
+        let normal = normal / normal_norm;
+        let before_impact = *dv * intersection.toi;
+        let after_impact = *dv - before_impact;
+        *dv = before_impact + after_impact - normal * (2.0 * after_impact.dot(&normal));
+        self.velocity -= normal * (2.0 * self.velocity.dot(&normal));
+        // The facing follows the velocity, see `vec_from_angle`.
+        self.facing = self.velocity.x.atan2(self.velocity.y);
+
+        false
+    }
 
-        graphics::draw_ex(ctx, &mesh, drawparam)?;
+    /// Wraps the position of the bullet around the arena exactly like the one of a Panda, see
+    /// `Panda::wrap_position`, so both live on the same torus.
+    fn wrap_position(&mut self, arena: &Arena, offset: f32) {
+        if self.pos.x < 0.0 - offset / 2.0 {
+            self.pos.x += arena.width + offset;
+        } else if self.pos.x > arena.width + offset / 2.0 {
+            self.pos.x -= arena.width + offset;
+        }
 
-        Ok(())
+        if self.pos.y < 0.0 - offset / 2.0 {
+            self.pos.y += arena.height + offset;
+        } else if self.pos.y > arena.height + offset / 2.0 {
+            self.pos.y -= arena.height + offset;
+        }
     }
 
-    /// Tells us if the bullet is outside the gameboard and should be removed from it.
-    pub fn in_bbox(&self, ctx: &mut Context) -> bool {
-        if self.pos.x < 0.0 || self.pos.x > ctx.conf.window_mode.width as f32 / 2.0 {
-            return false;
+    /// Mirrors the bullet back into the arena when it crosses an edge, along with its velocity.
+    fn bounce_off_edges(&mut self, arena: &Arena) {
+        let mut bounced = false;
+
+        if self.pos.x < 0.0 {
+            self.pos.x = (-self.pos.x).min(arena.width);
+            self.velocity.x = -self.velocity.x;
+            bounced = true;
+        } else if self.pos.x > arena.width {
+            self.pos.x = (2.0 * arena.width - self.pos.x).max(0.0);
+            self.velocity.x = -self.velocity.x;
+            bounced = true;
         }
-        if self.pos.y < 0.0 || self.pos.y > ctx.conf.window_mode.height as f32 / 2.0 {
-            return false;
+
+        if self.pos.y < 0.0 {
+            self.pos.y = (-self.pos.y).min(arena.height);
+            self.velocity.y = -self.velocity.y;
+            bounced = true;
+        } else if self.pos.y > arena.height {
+            self.pos.y = (2.0 * arena.height - self.pos.y).max(0.0);
+            self.velocity.y = -self.velocity.y;
+            bounced = true;
         }
-        true
+
+        if bounced {
+            // The facing follows the velocity, see `vec_from_angle`.
+            self.facing = self.velocity.x.atan2(self.velocity.y);
+        }
+    }
+
+    /// Queues the bullet in the sprite batches of the frame.
+    pub fn draw(&self, batches: &mut ActorBatches) {
+        batches.bullets.add(graphics::DrawParam {
+            dest: self.pos,
+            offset: Point2::new(0.5, 0.5),
+            scale: disc_scale(self.radius, self.radius),
+            color: Some(self.color.into()),
+            ..graphics::DrawParam::default()
+        });
+    }
+
+    /// Tells us if the bullet is still inside the arena or if it should be removed from it.
+    pub fn in_bbox(&self, arena: &Arena) -> bool {
+        arena.contains(&self.pos)
     }
 }
@@ -0,0 +1,244 @@
+//! The action decoders turn the output of the A.I. engine into a set of commands to a Panda.
+//! Which one is used is part of the config, and is saved along with the population so a loaded
+//! brain is decoded the way it was trained.
+
+use super::*;
+
+// The length of the output decoded as differences of absolute values, without the optional
+// actuators.
+const DIFFERENTIAL_BASE_OUTPUT_LEN: usize = 7;
+// The length of the output decoded as saturated axes, without the optional actuators.
+const TANH_BASE_OUTPUT_LEN: usize = 4;
+
+/// Turns the output of the A.I. engine into a set of commands to a Panda.
+pub trait ActionDecoder: Send + Sync {
+    /// Returns the length of the output the A.I. engine must compute for this decoder.
+    fn output_len(&self, config: &ActorConfig) -> usize;
+
+    /// Returns the commands given by the output of the A.I. engine.
+    fn decode(&self, output: &[f32], config: &ActorConfig) -> InputState;
+}
+
+/// Returns the action decoder of the given kind.
+pub fn action_decoder(kind: DecoderKind) -> Box<dyn ActionDecoder> {
+    match kind {
+        DecoderKind::Differential => Box::new(DifferentialDecoder),
+        DecoderKind::Tanh => Box::new(TanhDecoder),
+        DecoderKind::Argmax => Box::new(ArgmaxDecoder),
+    }
+}
+
+/// Each axis is the difference of the absolute values of 2 outputs, so the axes are unbounded,
+/// and a command is given when its output is positive.
+#[derive(Debug, Clone, Copy)]
+pub struct DifferentialDecoder;
+
+impl ActionDecoder for DifferentialDecoder {
+    fn output_len(&self, config: &ActorConfig) -> usize {
+        DIFFERENTIAL_BASE_OUTPUT_LEN + config.actuators.output_len()
+    }
+
+    fn decode(&self, input: &[f32], config: &ActorConfig) -> InputState {
+        // Thruster handler.
+        // let yaxis = input[0].abs();// + -input[1];
+        // let yaxis = input[0].abs() - (input[1].abs() * 0.25);
+        let yaxis = input[0].abs() - input[1].abs();
+        // let yaxis = input[0];
+
+        // Turn handler.
+        // input[1] == Turn left and input[2] == turn right
+        // let xaxis = input[1] - input[2];
+        let xaxis = input[2].abs() - input[3].abs();
+
+        // Sensor movement handler.
+        // let rs_axis = input[3] - input[4];
+        let fov_axis = input[4].abs() - input[5].abs();
+        // let fov_axis = input[4].log(10.0) - input[5].log(10.0);
+        // let fov_axis = input[4].fract();
+
+        // Handle the firing part based on an arbitrary threshold.
+        let fire = {
+            // if input[5] > 0.0 {
+            if input[6] > 0.0 {
+                true
+            } else {
+                false
+            }
+        };
+
+        let mut input_state = InputState {
+            xaxis,
+            yaxis,
+            fov_axis,
+            freeze: 0.0,
+            fire,
+            ..InputState::default()
+        };
+
+        // The optional actuators follow, in this order.
+        let mut idx = DIFFERENTIAL_BASE_OUTPUT_LEN;
+        let actuators = &config.actuators;
+        if actuators.strafe {
+            input_state.strafe = input[idx].abs() - input[idx + 1].abs();
+            idx += 2;
+        }
+        if actuators.sensor_control {
+            input_state.left_sensor_axis = input[idx].abs() - input[idx + 1].abs();
+            input_state.right_sensor_axis = input[idx + 2].abs() - input[idx + 3].abs();
+            idx += 4;
+        }
+        if actuators.brake {
+            input_state.brake = if input[idx] > 0.0 { 1.0 } else { 0.0 };
+        }
+
+        input_state
+    }
+}
+
+/// Each axis is a single output saturated by a tanh, so the axes stay within -1 and 1 whatever
+/// the magnitude of the output, and a command is given when its output is positive.
+#[derive(Debug, Clone, Copy)]
+pub struct TanhDecoder;
+
+impl ActionDecoder for TanhDecoder {
+    fn output_len(&self, config: &ActorConfig) -> usize {
+        let actuators = &config.actuators;
+        let mut len = TANH_BASE_OUTPUT_LEN;
+        if actuators.strafe {
+            len += 1;
+        }
+        if actuators.sensor_control {
+            len += 2;
+        }
+        if actuators.brake {
+            len += 1;
+        }
+        len
+    }
+
+    fn decode(&self, output: &[f32], config: &ActorConfig) -> InputState {
+        let mut input_state = InputState {
+            yaxis: output[0].tanh(),
+            xaxis: output[1].tanh(),
+            fov_axis: output[2].tanh(),
+            fire: output[3] > 0.0,
+            ..InputState::default()
+        };
+
+        // The optional actuators follow, in this order.
+        let mut idx = TANH_BASE_OUTPUT_LEN;
+        let actuators = &config.actuators;
+        if actuators.strafe {
+            input_state.strafe = output[idx].tanh();
+            idx += 1;
+        }
+        if actuators.sensor_control {
+            input_state.left_sensor_axis = output[idx].tanh();
+            input_state.right_sensor_axis = output[idx + 1].tanh();
+            idx += 2;
+        }
+        if actuators.brake {
+            input_state.brake = if output[idx] > 0.0 { 1.0 } else { 0.0 };
+        }
+
+        input_state
+    }
+}
+
+/// One of the discrete actions a Panda can take with the argmax decoder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Idle,
+    Forward,
+    Backward,
+    TurnLeft,
+    TurnRight,
+    OpenFov,
+    CloseFov,
+    Fire,
+    StrafeLeft,
+    StrafeRight,
+    OpenLeftSensors,
+    CloseLeftSensors,
+    OpenRightSensors,
+    CloseRightSensors,
+    Brake,
+}
+
+/// Each output stands for one action, and the Panda only takes the action of the highest output
+/// each tick, at full strength.
+#[derive(Debug, Clone, Copy)]
+pub struct ArgmaxDecoder;
+
+impl ArgmaxDecoder {
+    /// Returns the actions the Panda can take, in the order of the outputs.
+    fn actions(config: &ActorConfig) -> Vec<Action> {
+        let mut actions = vec![
+            Action::Idle,
+            Action::Forward,
+            Action::Backward,
+            Action::TurnLeft,
+            Action::TurnRight,
+            Action::OpenFov,
+            Action::CloseFov,
+            Action::Fire,
+        ];
+
+        let actuators = &config.actuators;
+        if actuators.strafe {
+            actions.extend_from_slice(&[Action::StrafeLeft, Action::StrafeRight]);
+        }
+        if actuators.sensor_control {
+            actions.extend_from_slice(&[
+                Action::OpenLeftSensors,
+                Action::CloseLeftSensors,
+                Action::OpenRightSensors,
+                Action::CloseRightSensors,
+            ]);
+        }
+        if actuators.brake {
+            actions.push(Action::Brake);
+        }
+
+        actions
+    }
+}
+
+impl ActionDecoder for ArgmaxDecoder {
+    fn output_len(&self, config: &ActorConfig) -> usize {
+        ArgmaxDecoder::actions(config).len()
+    }
+
+    fn decode(&self, output: &[f32], config: &ActorConfig) -> InputState {
+        let actions = ArgmaxDecoder::actions(config);
+
+        // The first of the highest outputs wins, so the decoding stays deterministic.
+        let mut best = 0;
+        for idx in 1..actions.len() {
+            if output[idx] > output[best] {
+                best = idx;
+            }
+        }
+
+        let mut input_state = InputState::default();
+        match actions[best] {
+            Action::Idle => {},
+            Action::Forward => input_state.yaxis = 1.0,
+            Action::Backward => input_state.yaxis = -1.0,
+            Action::TurnLeft => input_state.xaxis = 1.0,
+            Action::TurnRight => input_state.xaxis = -1.0,
+            Action::OpenFov => input_state.fov_axis = -1.0,
+            Action::CloseFov => input_state.fov_axis = 1.0,
+            Action::Fire => input_state.fire = true,
+            Action::StrafeLeft => input_state.strafe = 1.0,
+            Action::StrafeRight => input_state.strafe = -1.0,
+            Action::OpenLeftSensors => input_state.left_sensor_axis = -1.0,
+            Action::CloseLeftSensors => input_state.left_sensor_axis = 1.0,
+            Action::OpenRightSensors => input_state.right_sensor_axis = -1.0,
+            Action::CloseRightSensors => input_state.right_sensor_axis = 1.0,
+            Action::Brake => input_state.brake = 1.0,
+        }
+
+        input_state
+    }
+}
@@ -0,0 +1,61 @@
+//! Food lying around the arena, giving points or hit points to the panda picking it up.
+
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct Food {
+    // The position of the food in the arena.
+    pub pos: Point2,
+    // The ncollide2d shape to handle collision detection.
+    pub nshape: Ball<f32>,
+    // and its isometry.
+    pub iso: na::Isometry2<f32>,
+    // This is the object other actors will interact with to detect collision and ray casting.
+    pub body: Body,
+    // The radius of the food.
+    radius: f32,
+}
+
+impl Food {
+    /// Returns a new piece of food, spawned somewhere in the arena clear of the obstacles.
+    pub fn new<R: Rng>(
+        arena: &Arena,
+        obstacles: &Obstacles,
+        config: &FoodConfig,
+        rng: &mut R,
+    ) -> Self {
+        let pos = arena.random_clear_position(obstacles, config.radius, rng);
+        let nshape = Ball::new(config.radius);
+        let iso = na::Isometry2::new(na::Vector2::new(pos.x, pos.y), na::zero());
+
+        let mut body = Body::new(FOOD_TAG, BodyKind::Food, &nshape, &iso);
+        body.color = GREEN;
+
+        Food {
+            pos,
+            iso,
+            body,
+            nshape,
+            radius: config.radius,
+        }
+    }
+
+    /// Moves the food somewhere else in the arena clear of the obstacles, once it has been eaten.
+    pub fn respawn<R: Rng>(&mut self, arena: &Arena, obstacles: &Obstacles, rng: &mut R) {
+        self.pos = arena.random_clear_position(obstacles, self.radius, rng);
+        self.iso.translation.vector.x = self.pos.x;
+        self.iso.translation.vector.y = self.pos.y;
+        self.body.update(&self.iso);
+    }
+
+    /// Queues the food in the sprite batches of the frame.
+    pub fn draw(&self, batches: &mut ActorBatches) {
+        batches.food.add(graphics::DrawParam {
+            dest: self.pos,
+            offset: Point2::new(0.5, 0.5),
+            scale: disc_scale(self.radius, self.radius),
+            color: Some(GREEN.into()),
+            ..graphics::DrawParam::default()
+        });
+    }
+}
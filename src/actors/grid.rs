@@ -0,0 +1,306 @@
+//! A uniform grid over all the bodies of the gameboard, rebuilt each tick, so a ray cast only has
+//! to be tested against the bodies lying along the ray instead of every body.
+
+use super::*;
+use fnv::FnvHashMap;
+
+// The size of a cell of the grid, in world units.
+const GRID_CELL_SIZE: f32 = 32.0;
+// Margin added around each body when it's inserted in the grid, so a body touching the border
+// of a cell is also found from the neighbouring cell.
+const GRID_MARGIN: f32 = 1.0;
+
+#[derive(Debug, Clone)]
+pub struct BodyGrid {
+    // The size of each cell.
+    cell_size: f32,
+    // The indices of the bodies overlapping each cell.
+    cells: FnvHashMap<(i32, i32), Vec<usize>>,
+    // All the bodies of the gameboard, followed by their ghosts in a toroidal world.
+    bodies: Vec<Body>,
+    // The index of the original body of each body of the grid, ghost or not.
+    origins: Vec<usize>,
+}
+
+impl BodyGrid {
+    /// Returns a grid built from the bodies of the gameboard.
+    pub fn new(bodies: Vec<Body>) -> Self {
+        let origins: Vec<usize> = (0..bodies.len()).collect();
+        BodyGrid::from_bodies(bodies, origins)
+    }
+
+    /// Returns a grid built from the bodies of a world wrapped around a torus of the given size.
+    /// Each body whose copy, shifted by the size of the torus, lands within `reach` of the
+    /// `[0, torus_size]` area gets a ghost there, so the rays and contacts cross the seams.
+    pub fn new_wrapped(mut bodies: Vec<Body>, torus_size: (f32, f32), reach: f32) -> Self {
+        let (width, height) = torus_size;
+        let body_number = bodies.len();
+        let mut origins: Vec<usize> = (0..body_number).collect();
+
+        for idx in 0..body_number {
+            for &shift_x in &[-width, 0.0, width] {
+                for &shift_y in &[-height, 0.0, height] {
+                    if shift_x == 0.0 && shift_y == 0.0 {
+                        continue;
+                    }
+
+                    let mut ghost = bodies[idx].clone();
+                    ghost.isometry.translation.vector.x += shift_x;
+                    ghost.isometry.translation.vector.y += shift_y;
+
+                    let ghost_pos = ghost.isometry.translation.vector;
+                    if -reach <= ghost_pos.x && ghost_pos.x <= width + reach
+                        && -reach <= ghost_pos.y && ghost_pos.y <= height + reach
+                    {
+                        bodies.push(ghost);
+                        origins.push(idx);
+                    }
+                }
+            }
+        }
+
+        BodyGrid::from_bodies(bodies, origins)
+    }
+
+    /// Fills the cells of the grid with the bodies overlapping them.
+    fn from_bodies(bodies: Vec<Body>, origins: Vec<usize>) -> Self {
+        let mut grid = BodyGrid {
+            cell_size: GRID_CELL_SIZE,
+            cells: FnvHashMap::with_capacity_and_hasher(bodies.len(), Default::default()),
+            bodies,
+            origins,
+        };
+
+        for idx in 0..grid.bodies.len() {
+            let (min_cell, max_cell) = {
+                let body = &grid.bodies[idx];
+                let center = body.isometry.translation.vector;
+                let reach = body.nshape.radius() + GRID_MARGIN;
+                (
+                    grid.cell_of(center.x - reach, center.y - reach),
+                    grid.cell_of(center.x + reach, center.y + reach),
+                )
+            };
+
+            for cx in min_cell.0..max_cell.0 + 1 {
+                for cy in min_cell.1..max_cell.1 + 1 {
+                    grid.cells.entry((cx, cy)).or_insert_with(Vec::new).push(idx);
+                }
+            }
+        }
+
+        grid
+    }
+
+    /// Returns all the bodies of the gameboard, ghosts included.
+    pub fn bodies(&self) -> &[Body] {
+        &self.bodies
+    }
+
+    /// Returns the index of the original body of a body of the grid, which is the body itself
+    /// unless it's a ghost.
+    pub fn origin(&self, idx: usize) -> usize {
+        self.origins[idx]
+    }
+
+    /// Returns the coordinates of the cell containing a position.
+    fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Fills `candidates` with the indices of the bodies lying in the cells overlapped by a disc,
+    /// in the same order as the bodies were given to the grid.
+    pub fn bodies_near(&self, x: f32, y: f32, radius: f32, candidates: &mut Vec<usize>) {
+        candidates.clear();
+
+        let reach = radius + GRID_MARGIN;
+        let min_cell = self.cell_of(x - reach, y - reach);
+        let max_cell = self.cell_of(x + reach, y + reach);
+
+        for cx in min_cell.0..max_cell.0 + 1 {
+            for cy in min_cell.1..max_cell.1 + 1 {
+                if let Some(cell) = self.cells.get(&(cx, cy)) {
+                    candidates.extend_from_slice(cell);
+                }
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+
+    /// Fills `candidates` with the indices of the bodies lying in the cells crossed by the ray up
+    /// to `max_dist`, in the same order as the bodies were given to the grid.
+    /// The ray direction is expected to be a unit vector.
+    pub fn bodies_along_ray(
+        &self,
+        ray: &ncollide2d::query::Ray<f32>,
+        max_dist: f32,
+        candidates: &mut Vec<usize>,
+    ) {
+        candidates.clear();
+
+        let origin = ray.origin;
+        let dir = ray.dir;
+        let (mut cx, mut cy) = self.cell_of(origin.x, origin.y);
+
+        // We walk through the cells crossed by the ray, one border at a time (Amanatides & Woo).
+        let step_x: i32 = if dir.x > 0.0 { 1 } else { -1 };
+        let step_y: i32 = if dir.y > 0.0 { 1 } else { -1 };
+
+        let (mut t_max_x, t_delta_x) = if dir.x != 0.0 {
+            let border = (if dir.x > 0.0 { cx + 1 } else { cx }) as f32 * self.cell_size;
+            ((border - origin.x) / dir.x, self.cell_size / dir.x.abs())
+        } else {
+            (::std::f32::INFINITY, ::std::f32::INFINITY)
+        };
+        let (mut t_max_y, t_delta_y) = if dir.y != 0.0 {
+            let border = (if dir.y > 0.0 { cy + 1 } else { cy }) as f32 * self.cell_size;
+            ((border - origin.y) / dir.y, self.cell_size / dir.y.abs())
+        } else {
+            (::std::f32::INFINITY, ::std::f32::INFINITY)
+        };
+
+        loop {
+            if let Some(cell) = self.cells.get(&(cx, cy)) {
+                candidates.extend_from_slice(cell);
+            }
+
+            if t_max_x.min(t_max_y) > max_dist {
+                break;
+            }
+
+            if t_max_x < t_max_y {
+                cx += step_x;
+                t_max_x += t_delta_x;
+            } else {
+                cy += step_y;
+                t_max_y += t_delta_y;
+            }
+        }
+
+        // A body can overlap several cells, and the order of the bodies matters to break ties
+        // exactly like a test against every body would.
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ncollide2d::query::Ray;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // The distance a ray is cast to in the tests.
+    const MAX_DIST: f32 = 250.0;
+
+    /// Returns a board of balls of random sizes scattered around the origin, past both sides of
+    /// the axes so the negative cells are walked through too.
+    fn random_bodies(rng: &mut StdRng, body_number: usize) -> Vec<Body> {
+        (0..body_number)
+            .map(|tag| {
+                let x: f32 = rng.gen_range(-300.0, 300.0);
+                let y: f32 = rng.gen_range(-300.0, 300.0);
+                let radius: f32 = rng.gen_range(1.0, 12.0);
+                let iso = na::Isometry2::new(na::Vector2::new(x, y), na::zero());
+                Body::new(tag, BodyKind::Panda, &Ball::new(radius), &iso)
+            })
+            .collect()
+    }
+
+    /// Returns the body a sensor would see among the candidates, with the same tie breaking as
+    /// `Sensor::sens`, and its distance.
+    fn closest_hit(
+        bodies: &[Body],
+        candidates: &[usize],
+        ray: &Ray<f32>,
+    ) -> Option<(usize, f32)> {
+        let mut closest: Option<(usize, f32)> = None;
+        for &idx in candidates {
+            let body = &bodies[idx];
+            if let Some(toi) = body.nshape.toi_with_ray(&body.isometry, ray, true) {
+                let is_closer = match closest {
+                    Some((_, closest_toi)) => toi <= closest_toi,
+                    None => true,
+                };
+                if toi <= MAX_DIST && is_closer {
+                    closest = Some((idx, toi));
+                }
+            }
+        }
+        closest
+    }
+
+    /// Checks that a ray sees the same body through the grid as through every body.
+    fn assert_same_hit(grid: &BodyGrid, ray: &Ray<f32>) {
+        let bodies = grid.bodies();
+        let every_body: Vec<usize> = (0..bodies.len()).collect();
+        let mut candidates: Vec<usize> = Vec::new();
+        grid.bodies_along_ray(ray, MAX_DIST, &mut candidates);
+
+        assert_eq!(
+            closest_hit(bodies, &candidates, ray),
+            closest_hit(bodies, &every_body, ray),
+            "The grid disagrees with the brute-force version for {:?}",
+            ray
+        );
+    }
+
+    #[test]
+    fn bodies_along_ray_sees_like_every_body() {
+        let directions = [
+            na::Vector2::new(1.0, 0.0),
+            na::Vector2::new(-1.0, 0.0),
+            na::Vector2::new(0.0, 1.0),
+            na::Vector2::new(0.0, -1.0),
+        ];
+
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let grid = BodyGrid::new(random_bodies(&mut rng, 200));
+
+            for _ in 0..200 {
+                let x: f32 = rng.gen_range(-300.0, 300.0);
+                let y: f32 = rng.gen_range(-300.0, 300.0);
+                let angle: f32 = rng.gen_range(0.0, 2.0 * ::std::f32::consts::PI);
+                let random_direction = na_vec_from_angle(angle);
+
+                // Anywhere, exactly on the border of a cell, and on the corner of a cell.
+                let on_border_x = (x / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
+                let on_border_y = (y / GRID_CELL_SIZE).round() * GRID_CELL_SIZE;
+                let origins = [
+                    na::Point2::new(x, y),
+                    na::Point2::new(on_border_x, y),
+                    na::Point2::new(x, on_border_y),
+                    na::Point2::new(on_border_x, on_border_y),
+                ];
+
+                for origin in &origins {
+                    assert_same_hit(&grid, &Ray::new(*origin, random_direction));
+                    for direction in &directions {
+                        assert_same_hit(&grid, &Ray::new(*origin, *direction));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bodies_along_ray_sees_the_ghosts_like_every_body() {
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let grid = BodyGrid::new_wrapped(random_bodies(&mut rng, 200), (600.0, 600.0), 300.0);
+
+            for _ in 0..200 {
+                let origin = na::Point2::new(rng.gen_range(0.0, 600.0), rng.gen_range(0.0, 600.0));
+                let angle: f32 = rng.gen_range(0.0, 2.0 * ::std::f32::consts::PI);
+                assert_same_hit(&grid, &Ray::new(origin, na_vec_from_angle(angle)));
+            }
+        }
+    }
+}
@@ -3,59 +3,81 @@
 mod panda;
 mod sensor;
 mod bullet;
+mod food;
+mod grid;
+mod decoder;
+mod normalizer;
 
 pub use self::panda::*;
 pub use self::sensor::*;
 pub use self::bullet::*;
-
-
-use color_picker::BLACK2;
+pub use self::food::*;
+pub use self::grid::*;
+pub use self::decoder::*;
+pub use self::normalizer::*;
+
+
+use arena::Arena;
+use color_picker::{BLACK2, GREEN};
+use config::{
+    ActorConfig, BulletEdge, DecoderKind, FoodConfig, InputChannels, MovementModel, SensorConfig,
+};
+use obstacles::{BulletImpact, Obstacles};
+use sprites::{disc_scale, ActorBatches};
 use gameboard_controller::InputState;
 use ggez::graphics::{Point2, Vector2};
-use ggez::{graphics, nalgebra, Context, GameResult};
-use rand::{thread_rng, Rng};
+use ggez::{graphics, nalgebra, GameResult};
+use rand::Rng;
 
 use na;
 use ncollide2d;
 use ncollide2d::query::{self, RayCast};
 use ncollide2d::shape::Ball;
 
-// The area around a panda that trigger some score.
-// const HITBOX_SIZE: f32 = 2.5;
-const HITBOX_SIZE: f32 = 5.0;
-// The radius of the hitbox.
-const HITBOX_RADIUS: f32 = HITBOX_SIZE / 2.0;
-// The maximum velocity our stuff can reach.
-const MAX_PHYSICS_VEL: f32 = 250.0;
-// Acceleration in pixels per second.
-const ACTOR_THRUST: f32 = 2000.0;
-// Rotation in radians per second.
-const ACTOR_TURN_RATE: f32 = 1.0;
-// Turn rate of the sensor, it also define how close the sensor can get with each other.
-const SENSOR_TURN_RATE: f32 = 1.0;
+// All the tunable values (hitbox size, velocity, thrust, sensor range...) live in the
+// ActorConfig loaded from the simulation config file.
+
 // The value of nothingness from a sensor.
 const NOTHINGNESS: f32 = 999.0;
-// The distance a Sensor / eye can 'see'.
-// const SENSOR_MAX_DIST: f32 = HITBOX_SIZE * 100.0;
-const SENSOR_MAX_DIST: f32 = HITBOX_SIZE * 50.0;
-// The radius of a bullet hitbox.
-const BULLET_RADIUS: f32 = HITBOX_RADIUS / 1.5;
-// Bullet speed factor.
-const BULLET_SPEED_FACTOR: f32 = 3.00;
-// Time to wait between 2 shots in second.
-const SHOOTING_COOLDOWN: f32 = 1.0;
-// Number of output from each Sensor.
+// The tag of the food, which doesn't belong to any Panda.
+const FOOD_TAG: usize = ::std::usize::MAX;
+// Number of output from each Sensor, whatever the features in play.
 const SENSOR_OUTPUT_LEN: usize = 3;
-// The length of the output array that will be passed to the A.I. engine.
-pub const AI_ENGINE_INPUT_LEN: usize = 8;
-// The length of the output computed from the A.I. engine.
-// It correspond to the range of instructions a Panda
-// can receive from its 'brain'.
-pub const AI_ENGINE_OUTPUT_LEN: usize = 7;
+// Number of extra output from each Sensor when it tells about the kin of what it sees.
+const SENSOR_KIN_OUTPUT_LEN: usize = 4;
+// The closest a Sensor can get to the facing of its Panda, in radians.
+pub const SENSOR_MIN_ANGLE: f32 = 0.01;
+// The furthest a Sensor can get from the facing of its Panda, in radians.
+pub const SENSOR_MAX_ANGLE: f32 = 2.0;
 
 
 /// Some helper function.
 
+/// Returns the length of the output of each Sensor.
+fn sensor_output_len(config: &ActorConfig) -> usize {
+    let len = SENSOR_OUTPUT_LEN + config.channels.sensor_len();
+    if config.kin_channels {
+        len + SENSOR_KIN_OUTPUT_LEN
+    } else {
+        len
+    }
+}
+
+/// Returns the length of the output array that will be passed to the A.I. engine: the can-shoot
+/// flag, the FOV, every Sensor of the rig, the health if it's in play and the proprioception.
+pub fn ai_engine_input_len(config: &ActorConfig) -> usize {
+    2 + sensor_output_len(config) * config.sensors.len()
+        + config.channels.health as usize
+        + config.proprioception.input_len()
+}
+
+/// Returns the length of the output computed from the A.I. engine.
+/// It correspond to the range of instructions a Panda can receive from its 'brain', as read by
+/// its action decoder.
+pub fn ai_engine_output_len(config: &ActorConfig) -> usize {
+    action_decoder(config.decoder).output_len(config)
+}
+
 /// Create a unit vector representing the
 /// given angle (in radians)
 fn vec_from_angle(angle: f32) -> Vector2 {
@@ -72,32 +94,55 @@ fn na_vec_from_angle(angle: f32) -> na::Vector2<f32> {
 
 
 
+/// What a Body belongs to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyKind {
+    Panda,
+    Bullet,
+    Food,
+}
+
 /// Tis is a helper structure which aim at easing the interaction with ncollide2d and ray casting
-/// on Pandas and Bullets.
+/// on Pandas, Bullets and Food.
 #[derive(Debug, Clone)]
 pub struct Body {
     // This is the tag of a Panda or its bullet.
     pub tag: usize,
-    // Tells us if it's a bullet or not in order to prioritize bullets over anything else.
-    pub is_bullet: bool,
+    // Tells us what it is, so a sensor can tell what it sees.
+    pub kind: BodyKind,
+    // The team of the Panda it belongs to, in the team game mode.
+    pub team: Option<usize>,
     // The ncollide2d shape of the body we want to cast a ray upon.
     pub nshape: Ball<f32>,
     // And its isometry.
     pub isometry: na::Isometry2<f32>,
+    // How far it moved during the last tick, so the contacts can be swept along its path.
+    pub motion: na::Vector2<f32>,
+    // Its velocity during the last tick, in world units per second.
+    pub velocity: na::Vector2<f32>,
+    // Where it's facing, see `vec_from_angle`.
+    pub facing: f32,
+    // The color of the Panda it belongs to.
+    pub color: [f32; 4],
 }
 
 impl Body {
     pub fn new(
         tag: usize,
-        is_bullet: bool,
+        kind: BodyKind,
         nshape: &Ball<f32>,
         isometry: &na::Isometry2<f32>,
     ) -> Self {
         Body {
             tag,
-            is_bullet,
+            kind,
+            team: None,
             nshape: nshape.clone(),
             isometry: *isometry,
+            motion: na::zero(),
+            velocity: na::zero(),
+            facing: 0.0,
+            color: BLACK2,
         }
     }
 
@@ -107,9 +152,51 @@ impl Body {
         self.isometry.translation.vector.y = iso.translation.vector.y;
     }
 
-    /// Retruns if 2 bodies are in contact with each other.
-    pub fn in_contact(&self, body: &Body) -> bool {
-        let dist = query::distance(&self.isometry, &self.nshape, &body.isometry, &body.nshape);
-        dist <= 0.0
+    /// Retruns when 2 bodies first came in contact with each other during the last tick, as a
+    /// fraction of the tick, if they did, so a fast body can't go through another one between
+    /// two ticks.
+    pub fn swept_contact(&self, body: &Body) -> Option<f32> {
+        self.swept_contact_to(body, body.isometry.translation.vector)
+    }
+
+    /// Retruns when 2 bodies first came in contact with each other during the last tick in a
+    /// world wrapped around a torus of the given size, if they did, using the closest image of
+    /// the other body across the seams.
+    pub fn swept_contact_wrapped(&self, body: &Body, torus_size: (f32, f32)) -> Option<f32> {
+        let center = self.isometry.translation.vector;
+        let delta = body.isometry.translation.vector - center;
+        let dx = delta.x - torus_size.0 * (delta.x / torus_size.0).round();
+        let dy = delta.y - torus_size.1 * (delta.y / torus_size.1).round();
+
+        self.swept_contact_to(body, na::Vector2::new(center.x + dx, center.y + dy))
+    }
+
+    /// Sweeps the other body, ending the tick at the given position, against this one.
+    fn swept_contact_to(&self, body: &Body, end: na::Vector2<f32>) -> Option<f32> {
+        // Both bodies are balls, so this is the same as sweeping the center of the other body
+        // against a ball as large as both of them.
+        let radius = self.nshape.radius() + body.nshape.radius();
+        let center = self.isometry.translation.vector;
+
+        // From the point of view of this body, the other one moved along the difference of
+        // their motions.
+        let motion = body.motion - self.motion;
+        let start = end - motion;
+        if (start - center).norm_squared() <= radius * radius {
+            return Some(0.0);
+        }
+        if motion.norm_squared() == 0.0 {
+            return None;
+        }
+
+        // The direction of the ray is the whole motion, so the time of impact is a fraction of
+        // the tick and a contact happened during the tick if it's at most 1.
+        let ray = query::Ray::new(na::Point2::new(start.x, start.y), motion);
+        match Ball::new(radius).toi_with_ray(&self.isometry, &ray, true) {
+            Some(toi) if toi <= 1.0 => Some(toi),
+            // The rounding errors may push a contact at the very end of the tick past it.
+            _ if (end - center).norm_squared() <= radius * radius => Some(1.0),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,116 @@
+//! The observation normalisation layer, sitting between `Panda::build_output` and the A.I.
+//! engine. It maps every input channel to a documented range so they all weigh the same to the
+//! evolution:
+//!
+//! * the flags (can shoot, panda, bullet, and wall, food and ally when they are in play) are 0 or
+//!   1;
+//! * the FOV length is a share of the widest FOV, from 0 to 1;
+//! * the distance is 1 when touching, down to 0 at the range of the sensor, and -1 when the sensor
+//!   sees nothing;
+//! * the relative heading, the closing and the lateral speeds go from -1 to 1, and are 0 unless
+//!   the sensor sees a panda or a bullet;
+//! * the color similarity, the health and the distances to the walls go from 0 to 1;
+//! * the velocity and the angular velocity go from -1 to 1, the heading sine and cosine too.
+
+use super::*;
+use std::f32::consts::PI;
+
+// The value of a distance when the sensor sees nothing.
+const NOTHING_SEEN: f32 = -1.0;
+
+/// How a single input channel is normalised.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Channel {
+    // Already in its range.
+    Unchanged,
+    // Divided by a scale.
+    Scaled(f32),
+    // Divided by a scale and clamped within -1 and 1.
+    Clamped(f32),
+    // A distance seen by a sensor of the given range.
+    Distance(f32),
+}
+
+/// Maps the inputs of the A.I. engine to their documented range.
+#[derive(Debug, Clone)]
+pub struct InputNormalizer {
+    // One channel per input, in the order of `Panda::build_output`.
+    channels: Vec<Channel>,
+}
+
+impl InputNormalizer {
+    /// Returns the normaliser of the inputs built by the pandas with the given config.
+    pub fn new(config: &ActorConfig) -> Self {
+        let mut channels: Vec<Channel> = Vec::with_capacity(ai_engine_input_len(config));
+
+        // The can-shoot flag and the FOV. A rig looking straight ahead has no FOV at all.
+        channels.push(Channel::Unchanged);
+        let max_fov_length = config.max_fov_length();
+        channels.push(if max_fov_length > 0.0 {
+            Channel::Scaled(max_fov_length)
+        } else {
+            Channel::Unchanged
+        });
+
+        // A bullet goes as fast as a panda times the bullet speed factor, and both can go toward
+        // each other.
+        let max_closing_speed = config.max_physics_vel * (1.0 + config.bullet_speed_factor);
+        for sensor in &config.sensors {
+            // Bullet, panda, and the wall, food and ally flags in play.
+            channels.extend_from_slice(&[Channel::Scaled(10.0), Channel::Unchanged]);
+            for _ in 0..config.channels.sensor_len() {
+                channels.push(Channel::Unchanged);
+            }
+            channels.push(Channel::Distance(config.sensor_range(sensor)));
+            if config.kin_channels {
+                channels.extend_from_slice(&[
+                    Channel::Scaled(PI),
+                    Channel::Clamped(max_closing_speed),
+                    Channel::Clamped(max_closing_speed),
+                    Channel::Unchanged,
+                ]);
+            }
+        }
+
+        // The health.
+        if config.channels.health {
+            channels.push(Channel::Unchanged);
+        }
+
+        // The proprioception is already scaled, but a bump or the momentum can push it past.
+        let proprioception = &config.proprioception;
+        if proprioception.velocity {
+            channels.extend_from_slice(&[Channel::Clamped(1.0), Channel::Clamped(1.0)]);
+        }
+        if proprioception.angular_velocity {
+            channels.push(Channel::Clamped(1.0));
+        }
+        if proprioception.heading {
+            channels.extend_from_slice(&[Channel::Unchanged, Channel::Unchanged]);
+        }
+        if proprioception.walls {
+            channels.extend_from_slice(&[Channel::Unchanged; 4]);
+        }
+
+        InputNormalizer { channels }
+    }
+
+    /// Fills `normalized` with the inputs built by a panda, mapped to their range.
+    pub fn normalize(&self, inputs: &[f32], normalized: &mut Vec<f32>) {
+        normalized.clear();
+        normalized.extend(self.channels.iter().zip(inputs).map(|(channel, value)| {
+            match *channel {
+                Channel::Unchanged => *value,
+                Channel::Scaled(scale) => value / scale,
+                Channel::Clamped(scale) => (value / scale).max(-1.0).min(1.0),
+                Channel::Distance(range) => {
+                    if *value == -NOTHINGNESS {
+                        NOTHING_SEEN
+                    } else {
+                        value / range
+                    }
+                },
+            }
+        }));
+    }
+}
@@ -8,6 +8,8 @@ use super::*;
 pub struct Panda {
     // This is this id of our panda.
     pub tag: usize,
+    // Its team, in the team game mode.
+    pub team: Option<usize>,
     // An array of float representing the color of our panda.
     pub color: [f32; 4],
     // This actual position of our panda.
@@ -22,10 +24,15 @@ pub struct Panda {
     velocity: Vector2,
     // Field Od Vision length.
     fov_length: f32,
+    // How much the sensors on the left and on the right are opened, or closed if negative, away
+    // from their rest angle.
+    fov_spread: [f32; 2],
+    // The range each FOV spread can go through while keeping every sensor in its angle limits.
+    fov_spread_range: [(f32, f32); 2],
     // nalgebra velocity. Because ggez & nalgebra doesn't use the same object version
     // they are not interoperable with each other.
     // pub navelocity: na::Vector2<f32>,
-    // At this point in time, I'm not entirely sure what this is about ^^'.
+    // Rotation speed in radians per second.
     angle_vel: f32,
     // The size of the hitbox from the pos or the panda.
     hitbox_size: f32,
@@ -36,33 +43,74 @@ pub struct Panda {
     // This is the object other actors will interact with to detect collision and ray casting.
     // We build it during the creation of a Panda to avoid unnecessary allocation later.
     pub body: Body,
-    // Ray Left. It's basically a sensor from a "virtual" left eye.
-    pub sensor_left: Sensor,
-    // Ray Right. It's basically a sensor from a "virtual" right eye.
-    pub sensor_right: Sensor,
+    // The rig of sensors, each one being a "virtual" eye.
+    pub sensors: Vec<Sensor>,
     // Time to wait between 2 shots.
     cooldown: f32,
     // The score of our lovely beast. How well it's doing in this harsh world.
     pub score: f32,
+    // Its hit points, it's dead when there is none left.
+    pub health: f32,
+    // The hit points it's born with.
+    max_health: f32,
+    // Time in second spent alive during this round.
+    pub time_alive: f32,
+    // Time in second left before respawning, while dead.
+    pub respawn_countdown: f32,
     // This array is use to feed the A.I. engine to inform it about the state of one Panda.
-    pub input_to_ai: [f32; AI_ENGINE_INPUT_LEN],
+    pub input_to_ai: Vec<f32>,
 }
 
 impl Panda {
-    /// Retruns a freshly borned panda with name as a usize tag.
-    pub fn new(ctx: &Context, tag: usize, color: [f32; 4]) -> Self {
-        let pos = Point2::new(
-            thread_rng().gen_range(0.0, ctx.conf.window_mode.width as f32 / 2.0) as f32,
-            thread_rng().gen_range(0.0, ctx.conf.window_mode.height as f32 / 2.0) as f32,
-        );
+    /// Retruns a freshly borned panda with name as a usize tag, spawned somewhere in the arena
+    /// clear of the obstacles. Every random draw comes from the given generator so a round can be
+    /// replayed.
+    pub fn new<R: Rng>(
+        tag: usize,
+        color: [f32; 4],
+        arena: &Arena,
+        obstacles: &Obstacles,
+        config: &ActorConfig,
+        rng: &mut R,
+    ) -> Self {
+        let pos = arena.random_clear_position(obstacles, config.hitbox_radius(), rng);
 
         let na_pos = na::Point2::new(pos.x, pos.y);
-        let facing = thread_rng().gen_range(0.0, 360.0);
-        let nshape = ncollide2d::shape::Ball::new(HITBOX_RADIUS);
+        let facing = rng.gen_range(0.0, 360.0);
+        let nshape = ncollide2d::shape::Ball::new(config.hitbox_radius());
         let iso = na::Isometry2::new(na::Vector2::new(pos.x, pos.y), na::zero());
 
-        Panda {
+        let sensors: Vec<Sensor> = config
+            .sensors
+            .iter()
+            .map(|rig| Sensor::new(tag, na_pos, facing, rig, &color, config))
+            .collect();
+
+        // A sensor can't be closed past its minimum angle nor opened past its maximum one, the
+        // sensors looking straight ahead never move.
+        let fov_spread_range = {
+            let spread_range = |angles: [f32; 2], on_side: &dyn Fn(f32) -> bool| {
+                let side_offsets = || {
+                    sensors
+                        .iter()
+                        .filter(|sensor| on_side(sensor.offset))
+                        .map(|sensor| sensor.offset.abs())
+                };
+                (
+                    angles[0] - side_offsets().fold(angles[1], f32::min),
+                    angles[1] - side_offsets().fold(0.0, f32::max),
+                )
+            };
+            let actuators = &config.actuators;
+            [
+                spread_range(actuators.left_sensor_angles, &|offset| offset > 0.0),
+                spread_range(actuators.right_sensor_angles, &|offset| offset < 0.0),
+            ]
+        };
+
+        let mut panda = Panda {
             tag,
+            team: None,
             color,
             pos,
             isometry: iso,
@@ -70,290 +118,410 @@ impl Panda {
             direction_vector: vec_from_angle(facing),
             velocity: nalgebra::zero(),
             fov_length: 0.0,
+            fov_spread: [0.0, 0.0],
+            fov_spread_range,
             angle_vel: 0.0,
-            hitbox_size: HITBOX_SIZE,
-            radius: HITBOX_RADIUS,
-            body: Body::new(tag, false, &nshape, &iso),
+            hitbox_size: config.hitbox_size,
+            radius: config.hitbox_radius(),
+            body: Body::new(tag, BodyKind::Panda, &nshape, &iso),
             nshape,
-            sensor_left: Sensor::new(tag, na_pos, facing + 0.1, &color),
-            sensor_right: Sensor::new(tag, na_pos, facing - 0.1, &color),
+            sensors,
             cooldown: 0.0,
             score: 0.0,
-            input_to_ai: [0.0; AI_ENGINE_INPUT_LEN],
-        }
+            health: config.max_health,
+            max_health: config.max_health,
+            time_alive: 0.0,
+            respawn_countdown: 0.0,
+            input_to_ai: Vec::with_capacity(ai_engine_input_len(config)),
+        };
+        panda.body.facing = facing;
+        panda.body.color = color;
+        panda.update_fov_length();
+        panda
     }
 
     /// Updates our panda: cover everything from position to score etc.
-    pub fn update(&mut self, ctx: &mut Context, body_vec: &[Body], wrap_world: bool, dt: f32) -> GameResult<()> {
+    pub fn update(
+        &mut self,
+        grid: &BodyGrid,
+        obstacles: &Obstacles,
+        wrap_world: bool,
+        arena: &Arena,
+        config: &ActorConfig,
+        dt: f32,
+    ) -> GameResult<()> {
         // Clamp the velocity to the max efficiently
         let norm_sq = self.velocity.norm_squared();
-        if norm_sq > MAX_PHYSICS_VEL.powi(2) {
-            self.velocity = self.velocity / norm_sq.sqrt() * MAX_PHYSICS_VEL;
+        if norm_sq > config.max_physics_vel.powi(2) {
+            self.velocity = self.velocity / norm_sq.sqrt() * config.max_physics_vel;
             // self.navelocity = self.navelocity / self.navelocity.norm_squared() * MAX_PHYSICS_VEL;
         }
         let dv = self.velocity * dt;
-        self.pos += dv;
-
-        if wrap_world {
-            self.wrap_position(ctx);
-        } else {
-            self.confine_position(ctx);
-        }
-
-        self.isometry.translation.vector.x = self.pos.x;
-        self.isometry.translation.vector.y = self.pos.y;
-        self.body.update(&self.isometry);
+        let motion = self.move_by(dv, obstacles, wrap_world, arena);
+        self.body.motion = na::Vector2::new(motion.x, motion.y);
+        self.body.velocity = self.body.motion / dt;
+        self.body.facing = self.facing;
 
         // self.facing += self.angle_vel;
         let na_pos = na::Point2::new(self.pos.x, self.pos.y);
 
-        // print!("\tL dist = ");
-        self.sensor_left.update(na_pos, body_vec)?;
-        // print!("\tR dist = ");
-        self.sensor_right.update(na_pos, body_vec)?;
-        // println!("");
-
-        {
-            // let ls_out = self.sensor_left.output.clone();
-            let ls_out = self.sensor_left.output;
-            // let rs_out = self.sensor_right.output.clone();
-            let rs_out = self.sensor_right.output;
-            self.build_output(ls_out, rs_out);
+        let velocity = self.body.velocity;
+        for sensor in &mut self.sensors {
+            sensor.update(na_pos, velocity, grid, obstacles)?;
         }
+        self.build_output(wrap_world, arena, config);
         // Here we handle the possibility for a panda to shoot based on its cooldown.
         self.cooldown -= dt;
+        self.time_alive += dt;
 
         Ok(())
     }
 
-    /// Draw everything related to our panda.
-    pub fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        // We draw the sensors before the body of a Panda to avoid having the sensors's
-        // strait lines cutting the their bodies.
-        self.sensor_left.draw(ctx)?;
-        self.sensor_right.draw(ctx)?;
+    /// Moves the panda, keeping it in the arena and out of the obstacles, and returns the motion
+    /// its body went through.
+    fn move_by(
+        &mut self,
+        dv: Vector2,
+        obstacles: &Obstacles,
+        wrap_world: bool,
+        arena: &Arena,
+    ) -> Vector2 {
+        let previous_pos = self.pos;
+        self.pos += dv;
 
-        // Let's draw the body of our panda.
-        {
-            let mb = self.build_body_mesh(ctx)?;
-            let drawparam = graphics::DrawParam {
-                dest: self.pos,
-                rotation: -self.facing as f32,
-                offset: self.pos,
-                // offset: Point2::new(0.5, 0.5),
-                color: Some(self.color.into()),
-                ..graphics::DrawParam::default()
-            };
+        // A wrapped panda keeps its motion, so it's swept from the other side of the seam, while a
+        // confined one moved to wherever the walls put it.
+        let mut motion = if wrap_world {
+            self.wrap_position(arena);
+            dv
+        } else {
+            self.confine_position(arena);
+            self.pos - previous_pos
+        };
 
-            graphics::draw_ex(ctx, &mb, drawparam)?;
+        if !obstacles.is_empty() {
+            let shift = obstacles.push_out(na::Point2::new(self.pos.x, self.pos.y), self.radius);
+            let shift = Vector2::new(shift.x, shift.y);
+            let shift_norm = shift.norm();
+            if shift_norm > 0.0 {
+                self.pos += shift;
+                motion += shift;
+
+                // The panda slides along the obstacle instead of going through it.
+                let normal = shift / shift_norm;
+                let speed_into_obstacle = self.velocity.dot(&normal);
+                if speed_into_obstacle < 0.0 {
+                    self.velocity -= normal * speed_into_obstacle;
+                }
+            }
         }
 
+        self.isometry.translation.vector.x = self.pos.x;
+        self.isometry.translation.vector.y = self.pos.y;
+        self.body.update(&self.isometry);
+
+        motion
+    }
 
+    /// Pushes the panda away and changes its velocity, when it bumps into another panda.
+    pub fn bump(
+        &mut self,
+        shift: Vector2,
+        velocity_change: Vector2,
+        obstacles: &Obstacles,
+        wrap_world: bool,
+        arena: &Arena,
+    ) {
+        self.velocity += velocity_change;
+        let motion = self.move_by(shift, obstacles, wrap_world, arena);
+        self.body.motion += na::Vector2::new(motion.x, motion.y);
+    }
 
-        // Let's draw the 'eye' of our panda, it's basically a visual indicator of
-        // where it's actually looking.
-        {
-            let eye_mb = self.build_eye_mesh(ctx)?;
-            let eye_drawparam = graphics::DrawParam {
-                dest: self.pos,
-                rotation: -self.facing as f32,
-                offset: self.pos,
-                color: Some(BLACK2.into()),
-                ..graphics::DrawParam::default()
-            };
+    /// Tells us if our panda is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.health > 0.0
+    }
 
-            graphics::draw_ex(ctx, &eye_mb, eye_drawparam)?;
+    /// Removes some hit points from our panda, and returns if it died from it.
+    pub fn take_damage(&mut self, damage: f32) -> bool {
+        let was_alive = self.is_alive();
+        self.health -= damage;
+        was_alive && !self.is_alive()
+    }
+
+    /// Enrolls our panda and everything it owns in a team.
+    pub fn set_team(&mut self, team: Option<usize>) {
+        self.team = team;
+        self.body.team = team;
+        for sensor in &mut self.sensors {
+            sensor.team = team;
         }
+    }
 
-        Ok(())
+    /// Gives some hit points back to our panda, up to the ones it was born with.
+    pub fn heal(&mut self, health: f32) {
+        self.health = (self.health + health).min(self.max_health);
     }
 
-    /// We need to use a 'MeshBuilder' in order to be able to rotate the whole
-    /// actor structure when we turn.
-    fn build_body_mesh(&mut self, ctx: &mut Context) -> GameResult<graphics::Mesh> {
-        let mb = &mut graphics::MeshBuilder::new();
-        mb.circle(graphics::DrawMode::Fill, self.pos, self.hitbox_size, 0.1);
+    /// Brings our panda back to life somewhere in the arena clear of the obstacles, with all its
+    /// hit points.
+    pub fn respawn<R: Rng>(&mut self, arena: &Arena, obstacles: &Obstacles, rng: &mut R) {
+        self.pos = arena.random_clear_position(obstacles, self.radius, rng);
+        self.velocity = nalgebra::zero();
+        self.angle_vel = 0.0;
+        self.health = self.max_health;
+        self.cooldown = 0.0;
+        self.respawn_countdown = 0.0;
 
-        // Draws the body axis. Can be usefull diring debug.
-        // mb.line(&[self.pos, Point2::new(self.pos[0], self.pos[1] + 300.0)], 0.50);
+        self.isometry.translation.vector.x = self.pos.x;
+        self.isometry.translation.vector.y = self.pos.y;
+        self.body.update(&self.isometry);
+        self.body.motion = na::zero();
+        self.body.velocity = na::zero();
+    }
 
-        mb.build(ctx)
+    /// Returns the velocity of our panda.
+    pub fn velocity(&self) -> Vector2 {
+        self.velocity
     }
 
-    /// This is a graphical representation of the direction in which our actor is currently facing.
-    /// It's handled in a separate mesh so we can pick a different color from the body.
-    fn build_eye_mesh(&mut self, ctx: &mut Context) -> GameResult<graphics::Mesh> {
-        let mb = &mut graphics::MeshBuilder::new();
-
-        let ellipse_pos_x = self.pos[0];
-        let ellipse_pos_y = self.pos[1];
-        // The position of the eye as an ellipse, must be computed
-        // from the actual position of the actor and its body.
-        let ellipse_pos = Point2::new(ellipse_pos_x, ellipse_pos_y + HITBOX_SIZE * (3.0 / 3.5));
-
-        mb.ellipse(
-            graphics::DrawMode::Fill,
-            ellipse_pos,
-            HITBOX_SIZE / 2.,
-            HITBOX_SIZE / 4.0,
-            0.1,
-        );
-
-        mb.build(ctx)
+    /// Queues everything related to our panda in the sprite batches of the frame.
+    pub fn draw(&self, batches: &mut ActorBatches) {
+        // The sensors are in their own batch, drawn before the bodies to avoid having the
+        // sensors's strait lines cutting the their bodies.
+        for sensor in &self.sensors {
+            sensor.draw(batches);
+        }
+
+        // Let's draw the body of our panda.
+        batches.bodies.add(graphics::DrawParam {
+            dest: self.pos,
+            offset: Point2::new(0.5, 0.5),
+            scale: disc_scale(self.hitbox_size, self.hitbox_size),
+            color: Some(self.color.into()),
+            ..graphics::DrawParam::default()
+        });
+
+        // Let's draw the 'eye' of our panda, it's basically a visual indicator of
+        // where it's actually looking. Its position must be computed from the actual position
+        // of the actor and its body.
+        let eye_pos = self.pos + self.direction_vector * (self.hitbox_size * (3.0 / 3.5));
+        batches.eyes.add(graphics::DrawParam {
+            dest: eye_pos,
+            rotation: -self.facing as f32,
+            offset: Point2::new(0.5, 0.5),
+            scale: disc_scale(self.hitbox_size / 2.0, self.hitbox_size / 4.0),
+            color: Some(BLACK2.into()),
+            ..graphics::DrawParam::default()
+        });
     }
 
     /// This is where the magic of movement takes place.
+    /// Returns the bullet fired this tick, if any, so the pandas can be handled in parallel and
+    /// their bullets merged afterwards.
     pub fn handle_input(
         &mut self,
         input: &InputState,
-        bullet_vector: &mut Vec<Bullet>,
+        config: &ActorConfig,
         dt: f32,
-    ) {
-        let turn = dt * ACTOR_TURN_RATE * input.xaxis;
-        self.facing += turn;
-        self.direction_vector = vec_from_angle(self.facing);
-
-        // Turn the sensors along the entire body.
-        self.sensor_left.facing += turn;
-        self.sensor_right.facing += turn;
-
-        if input.yaxis != 0.0 {
-            let thrust_vector = self.direction_vector * (ACTOR_THRUST);
-
-            if input.yaxis > 0.0 {
-                self.velocity += thrust_vector * (dt);
-            } else {
-                self.velocity -= thrust_vector * (dt);
-            }
-        } else {
-            self.velocity *= 0.0;
+    ) -> Option<Bullet> {
+        match config.movement.model {
+            MovementModel::Instant => self.move_instantly(input, config, dt),
+            MovementModel::Momentum => self.move_with_momentum(input, config, dt),
         }
 
         // Freeze all motor functions.
         if input.freeze > 0.0 {
             self.velocity = nalgebra::zero();
+            self.angle_vel = 0.0;
         }
 
-        // Compute this only once.
-        let atan_direction_vector = self.direction_vector.y.atan2(self.direction_vector.x);
+        // Slow everything down.
+        if input.brake > 0.0 {
+            let kept = (1.0 - config.actuators.brake_drag * dt).max(0.0);
+            self.velocity *= kept;
+            self.angle_vel *= kept;
+        }
 
         // FOV input handling part.
-        // Now it's used to handle the synchronized left and right Sensor movements.
-        let future_fov_turn = dt * SENSOR_TURN_RATE * input.fov_axis;
-        let future_fov_vector = vec_from_angle(self.sensor_right.facing + future_fov_turn);
-        let tan_diff = atan_direction_vector - future_fov_vector.y.atan2(future_fov_vector.x);
-
-
-        // println!("input.fov_axis = {} , tan_diff = {}", input.fov_axis, tan_diff);
-
-        // Only a test on one of the sensor is enough to check if the next movement is in
-        // range of the sensors angle limitation.
-        if -2.0 <= tan_diff && tan_diff <= -0.01 {
-            // Open or close sensors in sync.
-            self.sensor_right.facing += dt * SENSOR_TURN_RATE * input.fov_axis;
-            self.sensor_left.facing -= dt * SENSOR_TURN_RATE * input.fov_axis;
-
-            let rs_vector = vec_from_angle(self.sensor_right.facing);
-            let ls_vector = vec_from_angle(self.sensor_left.facing);
-            // let tan_diff = rs_vector.y.atan2(rs_vector.x) - ls_vector.y.atan2(ls_vector.x);
-            // println!("tan_diff = {}", tan_diff);
-            self.fov_length = rs_vector.y.atan2(rs_vector.x) - ls_vector.y.atan2(ls_vector.x);
+        // It opens or closes all the sensors of the rig in sync, away from or toward the facing,
+        // as long as the next movement keeps every sensor in range of its angle limitation.
+        let fov_turn = -dt * config.sensor_turn_rate * input.fov_axis;
+        let future_fov_spread = [self.fov_spread[0] + fov_turn, self.fov_spread[1] + fov_turn];
+        if (0..2).all(|side| self.spread_in_range(side, future_fov_spread[side])) {
+            self.fov_spread = future_fov_spread;
+        }
+
+        // Then the sensors on each side on their own.
+        let side_axes = [input.left_sensor_axis, input.right_sensor_axis];
+        for side in 0..2 {
+            let future_spread =
+                self.fov_spread[side] - dt * config.sensor_turn_rate * side_axes[side];
+            if self.spread_in_range(side, future_spread) {
+                self.fov_spread[side] = future_spread;
+            }
+        }
+        self.update_fov_length();
+
+        // Turn the sensors along the entire body.
+        for sensor in &mut self.sensors {
+            sensor.facing = self.facing + sensor.relative_angle(&self.fov_spread);
         }
 
 
         // Here we handle the fire situations.
         if input.fire && self.cooldown < 0.0 {
-            self.cooldown = SHOOTING_COOLDOWN;
+            self.cooldown = config.shooting_cooldown;
 
-            let bullet: Bullet = Bullet::new(self.tag, self.pos, self.facing, &self.color);
-            bullet_vector.push(bullet);
+            let bullet: Bullet =
+                Bullet::new(self.tag, self.team, self.pos, self.facing, &self.color, config);
+            Some(bullet)
+        } else {
+            None
         }
     }
 
-    /// This is where the A.I. engine works its magic.
-    /// This is where we convert the orders from the A.I. engine to a set of commands to a Panda.
-    pub fn build_input_from_ai(input: &[f32]) -> InputState {
-        // Thruster handler.
-        // let yaxis = input[0].abs();// + -input[1];
-        // let yaxis = input[0].abs() - (input[1].abs() * 0.25);
-        let yaxis = input[0].abs() - input[1].abs();
-        // let yaxis = input[0];
-
-        // Turn handler.
-        // input[1] == Turn left and input[2] == turn right
-        // let xaxis = input[1] - input[2];
-        let xaxis = input[2].abs() - input[3].abs();
-
-        // Sensor movement handler.
-        // let rs_axis = input[3] - input[4];
-        let fov_axis = input[4].abs() - input[5].abs();
-        // let fov_axis = input[4].log(10.0) - input[5].log(10.0);
-        // let fov_axis = input[4].fract();
-
-        // Handle the firing part based on an arbitrary threshold.
-        let fire = {
-            // if input[5] > 0.0 {
-            if input[6] > 0.0 {
-                true
-            } else {
-                false
+    /// Turns at the turn rate, and thrusts or stops right away.
+    fn move_instantly(&mut self, input: &InputState, config: &ActorConfig, dt: f32) {
+        self.angle_vel = config.actor_turn_rate * input.xaxis;
+        self.facing += dt * self.angle_vel;
+        self.direction_vector = vec_from_angle(self.facing);
+
+        if input.yaxis != 0.0 || input.strafe != 0.0 {
+            let thrust_vector = self.direction_vector * (config.actor_thrust);
+
+            if input.yaxis > 0.0 {
+                self.velocity += thrust_vector * (dt);
+            } else if input.yaxis < 0.0 {
+                self.velocity -= thrust_vector * (dt);
             }
-        };
 
-        InputState {
-            xaxis,
-            yaxis,
-            fov_axis,
-            freeze: 0.0,
-            fire,
+            let strafe_vector =
+                self.left_vector() * (config.actor_thrust * config.actuators.strafe_thrust);
+            if input.strafe > 0.0 {
+                self.velocity += strafe_vector * (dt);
+            } else if input.strafe < 0.0 {
+                self.velocity -= strafe_vector * (dt);
+            }
+        } else {
+            self.velocity *= 0.0;
         }
     }
 
-    /// Build the array we need to feed the A.I. engine with from the state of our Panda and its
-    /// Sensors.
-    fn build_output(&mut self, left_sensor_output: [f32; SENSOR_OUTPUT_LEN], right_sensor_output: [f32; SENSOR_OUTPUT_LEN]) {
-        let mut start_idx: usize = 0;
+    /// Accelerates the rotation and the motion of our panda, the drags slowing both down.
+    fn move_with_momentum(&mut self, input: &InputState, config: &ActorConfig, dt: f32) {
+        let movement = &config.movement;
+
+        // The torque is set so the drag balances it at the turn rate, for a mass of 1. The drag
+        // is checked to be strictly positive when loading the config, or it couldn't turn.
+        let torque = config.actor_turn_rate * movement.angular_drag * input.xaxis;
+        self.angle_vel += torque / movement.mass * dt;
+        self.angle_vel *= (1.0 - movement.angular_drag * dt).max(0.0);
+        self.facing += dt * self.angle_vel;
+        self.direction_vector = vec_from_angle(self.facing);
 
-        if self.cooldown <= 0.0 {
-            // Signify that we can shoot.
-            self.input_to_ai[start_idx] = 1.0;
+        let thrust = if input.yaxis > 0.0 {
+            config.actor_thrust
+        } else if input.yaxis < 0.0 {
+            -config.actor_thrust * movement.reverse_thrust
         } else {
-            self.input_to_ai[start_idx] = 0.0;
-        }
-        start_idx += 1;
+            0.0
+        };
+        let strafe_thrust = config.actor_thrust * config.actuators.strafe_thrust;
+        let strafe = if input.strafe > 0.0 {
+            strafe_thrust
+        } else if input.strafe < 0.0 {
+            -strafe_thrust
+        } else {
+            0.0
+        };
+        let force = self.direction_vector * thrust + self.left_vector() * strafe;
+        self.velocity += force * (dt / movement.mass);
+        self.velocity *= (1.0 - movement.linear_drag * dt).max(0.0);
+    }
+
+    /// Returns the unit vector pointing to the left of our panda, see `vec_from_angle`.
+    fn left_vector(&self) -> Vector2 {
+        Vector2::new(self.direction_vector.y, -self.direction_vector.x)
+    }
+
+    /// Tells us if the sensors on the left (0) or the right (1) side stay in their angle limits
+    /// with the given spread.
+    fn spread_in_range(&self, side: usize, spread: f32) -> bool {
+        let (min_spread, max_spread) = self.fov_spread_range[side];
+        min_spread <= spread && spread <= max_spread
+    }
+
+    /// Computes the angle between the outermost sensors of the rig.
+    fn update_fov_length(&mut self) {
+        let fov_spread = self.fov_spread;
+        let angles = self.sensors.iter().map(|sensor| sensor.relative_angle(&fov_spread));
+        let (min_angle, max_angle) = angles.fold((0.0, 0.0), |(min, max): (f32, f32), angle| {
+            (min.min(angle), max.max(angle))
+        });
+
+        self.fov_length = max_angle - min_angle;
+    }
+
+    /// Build the array we need to feed the A.I. engine with from the state of our Panda and its
+    /// Sensors. The `InputNormalizer` follows the same order.
+    fn build_output(&mut self, wrap_world: bool, arena: &Arena, config: &ActorConfig) {
+        self.input_to_ai.clear();
+
+        // Signify whether we can shoot.
+        self.input_to_ai.push(if self.cooldown <= 0.0 { 1.0 } else { 0.0 });
 
         // FOV handler.
-        self.input_to_ai[start_idx] = self.fov_length;
-        start_idx += 1;
+        self.input_to_ai.push(self.fov_length);
 
-        for value in left_sensor_output.iter() {
-            self.input_to_ai[start_idx] = *value;
-            start_idx += 1;
+        for sensor in &self.sensors {
+            self.input_to_ai.extend_from_slice(&sensor.output);
         }
 
-        for value in right_sensor_output.iter() {
-            self.input_to_ai[start_idx] = *value;
-            start_idx += 1;
+        // Its own health, from 1 when it's untouched down to 0 when it's about to die.
+        if config.channels.health {
+            self.input_to_ai.push(self.health.max(0.0) / self.max_health);
         }
 
-        // Print this for debugging purpose.
-        // println!("tag \tpos.x \tpos.y \tdVec.x \tdVec.y \tvel.x \tvel.y \
-        //          \tLdV.x \tLdV.y \tdist \tBullet \tPanda \tRdV.x \tRdV.y \tdist \tBullet \tPanda");
-        // print!("{}", self.tag);
-        // for i in self.output.iter() {
-        //     print!(" \t{:.2}", i);
-        // }
-        // println!("");
+        // What it feels about its own state.
+        let proprioception = &config.proprioception;
+        if proprioception.velocity {
+            let forward = self.direction_vector;
+            let velocity = self.body.velocity;
+            self.input_to_ai.push(
+                (velocity.x * forward.x + velocity.y * forward.y) / config.max_physics_vel,
+            );
+            // The left of the panda, see `vec_from_angle`.
+            self.input_to_ai.push(
+                (velocity.x * forward.y - velocity.y * forward.x) / config.max_physics_vel,
+            );
+        }
+        if proprioception.angular_velocity {
+            self.input_to_ai.push(self.angle_vel / config.actor_turn_rate);
+        }
+        if proprioception.heading {
+            self.input_to_ai.push(self.facing.sin());
+            self.input_to_ai.push(self.facing.cos());
+        }
+        if proprioception.walls {
+            if wrap_world {
+                self.input_to_ai.extend_from_slice(&[1.0; 4]);
+            } else {
+                self.input_to_ai.extend_from_slice(&[
+                    self.pos.x / arena.width,
+                    (arena.width - self.pos.x) / arena.width,
+                    self.pos.y / arena.height,
+                    (arena.height - self.pos.y) / arena.height,
+                ]);
+            }
+        }
     }
 
-    /// Takes a Panda and wraps its position to bounds of the gameboard, so if it goes off the left
-    /// side of the gameboard it will reappear on the right side and so on.
-    fn wrap_position(&mut self, ctx: &mut Context) {
-        let height = ctx.conf.window_mode.height as f32;
-        let width = ctx.conf.window_mode.width as f32;
-
-        let x_bound = width / 2.0;
-        let y_bound = height / 2.0;
+    /// Takes a Panda and wraps its position to bounds of the arena, so if it goes off the left
+    /// side of the arena it will reappear on the right side and so on.
+    fn wrap_position(&mut self, arena: &Arena) {
+        let x_bound = arena.width;
+        let y_bound = arena.height;
 
         let offset: f32 = self.hitbox_size * 2.0;
 
@@ -372,12 +540,9 @@ impl Panda {
 
 
     /// Do not wrap the positions the Panda and confine them in the gamboad instead.
-    fn confine_position(&mut self, ctx: &mut Context) {
-        let height = ctx.conf.window_mode.height as f32;
-        let width = ctx.conf.window_mode.width as f32;
-
-        let x_bound = width / 2.0;
-        let y_bound = height / 2.0;
+    fn confine_position(&mut self, arena: &Arena) {
+        let x_bound = arena.width;
+        let y_bound = arena.height;
 
         let offset: f32 = self.hitbox_size * 2.0;
 
@@ -6,6 +6,8 @@ use super::*;
 pub struct Sensor {
     // The tag of the panda owning this sensor.
     tag: usize,
+    // The team of the panda owning this sensor, in the team game mode.
+    pub team: Option<usize>,
     // The distance from the origin of the ray.
     // NOTHINGNESS means seeing nothing.
     pub distance: f32,
@@ -13,6 +15,26 @@ pub struct Sensor {
     pub is_panda: f32,
     // Or is it a bullet ?
     pub is_bullet: f32,
+    // Or an obstacle ?
+    pub is_wall: f32,
+    // Or some food ?
+    pub is_food: f32,
+    // Does the panda or bullet we are seeing belong to an ally ?
+    pub is_ally: f32,
+    // The angle between the heading of the panda or bullet we are seeing and the way back to
+    // us, from -PI to PI. It's 0 when it's heading straight at us.
+    pub relative_heading: f32,
+    // How fast the panda or bullet we are seeing is getting closer, negative when it's going
+    // away.
+    pub closing_speed: f32,
+    // How fast it's crossing the ray, positive when it's going toward the left of the sensor.
+    pub lateral_speed: f32,
+    // How close its color is to ours, from 0 to 1 when it's exactly the same.
+    pub color_similarity: f32,
+    // Do we tell the A.I. engine about the kin of what we are seeing ?
+    kin_channels: bool,
+    // Which of the wall, food and ally flags we tell the A.I. engine about.
+    channels: InputChannels,
     // The ray casting technology that "sees".
     pub ray: ncollide2d::query::Ray<f32>,
     // The position as ggez::Point2.
@@ -21,34 +43,82 @@ pub struct Sensor {
     pub na_pos: na::Point2<f32>,
     // The direction where the ray is fired.
     pub facing: f32,
+    // The angle between the sensor and the facing of its panda at rest.
+    pub offset: f32,
     // Facing representation as a Vector2.
     direction_vector: Vector2,
     // The color of the panda owning this sensor.
     pub color: [f32; 4],
+    // The distance this sensor can 'see'.
+    max_dist: f32,
+    // The thickness of the Sensor's body when drawn.
+    thickness: f32,
     // This array is use to feed the A.I. engine to inform it about what a Panda 'sense'.
-    pub output: [f32; SENSOR_OUTPUT_LEN],
+    pub output: Vec<f32>,
 }
 
 impl Sensor {
-    /// Returns a new sensor init from a position and an angle.
-    pub fn new(tag: usize, na_pos: na::Point2<f32>, angle: f32, color: &[f32; 4]) -> Self {
+    /// Returns a new sensor of the rig init from the position and the facing of its panda.
+    pub fn new(
+        tag: usize,
+        na_pos: na::Point2<f32>,
+        panda_facing: f32,
+        rig: &SensorConfig,
+        color: &[f32; 4],
+        config: &ActorConfig,
+    ) -> Self {
+        let angle = panda_facing + rig.offset;
+
         Sensor {
             tag,
+            team: None,
             distance: -NOTHINGNESS,
             is_panda: 0.0,
             is_bullet: 0.0,
+            is_wall: 0.0,
+            is_food: 0.0,
+            is_ally: 0.0,
+            relative_heading: 0.0,
+            closing_speed: 0.0,
+            lateral_speed: 0.0,
+            color_similarity: 0.0,
+            kin_channels: config.kin_channels,
+            channels: config.channels,
             ray: ncollide2d::query::Ray::new(na_pos, na_vec_from_angle(angle)),
             pos: Point2::new(na_pos.x, na_pos.y),
             na_pos,
             facing: angle,
+            offset: rig.offset,
             direction_vector: vec_from_angle(angle),
             color: *color,
-            output: [0.0; SENSOR_OUTPUT_LEN],
+            max_dist: config.sensor_range(rig),
+            thickness: config.bullet_radius() * 2.0,
+            output: vec![0.0; sensor_output_len(config)],
+        }
+    }
+
+    /// Returns the angle of the sensor relative to the facing of its panda once the sensors on
+    /// the left and on the right are opened by `fov_spread`. A sensor looking straight ahead
+    /// doesn't move.
+    pub fn relative_angle(&self, fov_spread: &[f32; 2]) -> f32 {
+        if self.offset > 0.0 {
+            self.offset + fov_spread[0]
+        } else if self.offset < 0.0 {
+            self.offset - fov_spread[1]
+        } else {
+            0.0
         }
     }
 
-    /// Updates the state of the sensor (position, facing angle, etc.)
-    pub fn update(&mut self, na_pos: na::Point2<f32>, body_vec: &[Body]) -> GameResult<()> {
+    /// Updates the state of the sensor (position, facing angle, etc.) from the position and the
+    /// velocity of its panda.
+    pub fn update(
+        &mut self,
+        na_pos: na::Point2<f32>,
+        velocity: na::Vector2<f32>,
+        grid: &BodyGrid,
+        obstacles: &Obstacles,
+    ) -> GameResult<()> {
         self.na_pos = na_pos;
         self.pos.x = na_pos.coords[0];
         self.pos.y = na_pos.coords[1];
@@ -57,88 +127,87 @@ impl Sensor {
         self.direction_vector.x = self.ray.dir.data[0];
         self.direction_vector.y = self.ray.dir.data[1];
 
-        self.sens(body_vec);
+        self.sens(velocity, grid, obstacles);
         self.build_output();
 
         Ok(())
     }
 
-    /// Draws the graphical representation of a sensor as a strait line symbolizing a ray cast.
-    pub fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let mb = self.build_ray_mesh(ctx)?;
-        let drawparam = graphics::DrawParam {
+    /// Queues the graphical representation of a sensor, a strait line symbolizing a ray cast,
+    /// in the sprite batches of the frame.
+    pub fn draw(&self, batches: &mut ActorBatches) {
+        batches.sensors.add(graphics::DrawParam {
             dest: self.pos,
             rotation: -self.facing as f32,
-            offset: self.pos,
+            // The line starts from the middle of the top of the pixel image.
+            offset: Point2::new(0.5, 0.0),
+            // Draws a shorter version of the sensor for a cleaner gameboard.
+            scale: Point2::new(self.thickness, self.max_dist / 10.0),
             color: Some(self.color.into()),
             // Draws the sensors in a slightly more black color.
             // color: Some(BLACK2.into()),
             ..graphics::DrawParam::default()
-        };
-
-        graphics::draw_ex(ctx, &mb, drawparam)?;
-
-        Ok(())
+        });
     }
 
-    /// This is the graphical representation of the sensor using a MeshBuilder to easily rotate
-    /// etc.
-    fn build_ray_mesh(&self, ctx: &mut Context) -> GameResult<graphics::Mesh> {
-        let mb = &mut graphics::MeshBuilder::new();
-        mb.line(
-            &[
-                self.pos,
-                Point2::new(
-                    self.na_pos.x,
-                    // Draws a shorter version of the sensor for a cleaner gameboard.
-                    self.na_pos.y + SENSOR_MAX_DIST / 10.0,
-                    // Draws the actual size of the sensor.
-                    // self.na_pos.y + (SENSOR_MAX_DIST * 2.0),
-                ),
-            ],
-            BULLET_RADIUS * 2.0, // The thickness of the Sensor's body
-        );
-        // mb.line(
-        //     &[
-        //         self.pos,
-        //         Point2::new(
-        //             self.na_pos.x,
-        //             // Draws a shorter version of the sensor for a cleaner gameboard.
-        //             // self.na_pos.y + SENSOR_MAX_DIST / 10.0,
-        //             // Draws the actual size of the sensor.
-        //             self.na_pos.y + (SENSOR_MAX_DIST * 2.0),
-        //         ),
-        //     ],
-        //     0.25, // The thickness of the Sensor's body
-        // );
-
-        mb.build(ctx)
-    }
-
-    /// Returns the distance of an actor if the sensor 'sees' it, or nothing otherwise.
-    pub fn sens(&mut self, body_vec: &[Body]) {
+    /// Returns the distance of an actor or an obstacle if the sensor 'sees' it, or nothing
+    /// otherwise.
+    pub fn sens(&mut self, velocity: na::Vector2<f32>, grid: &BodyGrid, obstacles: &Obstacles) {
         self.distance = NOTHINGNESS;
         self.is_panda = 0.0;
         self.is_bullet = 0.0;
+        self.is_wall = 0.0;
+        self.is_food = 0.0;
+        self.is_ally = 0.0;
+        self.clear_kin();
+
+        // Only the bodies lying along the ray, within reach of the sensor, can be seen.
+        let mut candidates: Vec<usize> = Vec::new();
+        grid.bodies_along_ray(&self.ray, self.max_dist, &mut candidates);
+        let body_vec = grid.bodies();
+
         // Here we iter through all body we can possibly interact with and get the distance from it
         // if the ray casting encounter it.
-        for body in body_vec.iter() {
+        for body in candidates.iter().map(|idx| &body_vec[*idx]) {
             // We need to filter the objects belonging to a panda to not interact with them.
             if body.tag != self.tag {
                 let dist = self.get_distance(&body);
-                if dist <= SENSOR_MAX_DIST {
-                    if dist <= self.distance && body.is_bullet {
-                        self.distance = dist;
-                        self.is_bullet = 10.0;
-                        self.is_panda = 0.0;
-                    } else if dist <= self.distance && !body.is_bullet {
-                        self.distance = dist;
-                        self.is_bullet = 0.0;
-                        self.is_panda = 1.0;
+                if dist <= self.max_dist && dist <= self.distance {
+                    self.distance = dist;
+                    self.is_bullet = 0.0;
+                    self.is_panda = 0.0;
+                    self.is_food = 0.0;
+                    match body.kind {
+                        BodyKind::Bullet => self.is_bullet = 10.0,
+                        BodyKind::Panda => self.is_panda = 1.0,
+                        BodyKind::Food => self.is_food = 1.0,
+                    }
+                    self.is_ally = if self.team.is_some() && body.team == self.team {
+                        1.0
+                    } else {
+                        0.0
+                    };
+                    match body.kind {
+                        BodyKind::Bullet | BodyKind::Panda => self.sens_kin(body, velocity),
+                        BodyKind::Food => self.clear_kin(),
                     }
                 }
             }
         }
+
+        // An obstacle hides whatever stands behind it.
+        if let Some(intersection) = obstacles.cast_ray(&self.ray) {
+            let dist = intersection.toi;
+            if dist <= self.max_dist && dist < self.distance {
+                self.distance = dist;
+                self.is_bullet = 0.0;
+                self.is_panda = 0.0;
+                self.is_food = 0.0;
+                self.is_ally = 0.0;
+                self.is_wall = 1.0;
+                self.clear_kin();
+            }
+        }
         // This is a tweak to help the ANN to better precess distances.
         if self.distance == NOTHINGNESS {
             self.distance *= -1.0;
@@ -146,10 +215,35 @@ impl Sensor {
             // Here we change the distance value of the object we 'see' to be inversely
             // proportionnate to its actual value so a closer object will have a higher
             // positive value than a distant one.
-            self.distance = SENSOR_MAX_DIST - self.distance;
+            self.distance = self.max_dist - self.distance;
         }
     }
 
+    /// Tells about the kin of a panda or a bullet we are seeing, from the velocity of our panda.
+    fn sens_kin(&mut self, body: &Body, velocity: na::Vector2<f32>) {
+        let back = -self.ray.dir;
+        let heading = na_vec_from_angle(body.facing);
+        self.relative_heading = (back.x * heading.y - back.y * heading.x).atan2(back.dot(&heading));
+
+        // The left of the sensor, see `vec_from_angle`.
+        let left = na::Vector2::new(self.ray.dir.y, -self.ray.dir.x);
+        let relative_velocity = body.velocity - velocity;
+        self.closing_speed = relative_velocity.dot(&back);
+        self.lateral_speed = relative_velocity.dot(&left);
+
+        // Only the red, green and blue parts matter.
+        let color_diff: f32 = (0..3).map(|i| (body.color[i] - self.color[i]).abs()).sum();
+        self.color_similarity = 1.0 - color_diff / 3.0;
+    }
+
+    /// Forgets about the kin of what we were seeing.
+    fn clear_kin(&mut self) {
+        self.relative_heading = 0.0;
+        self.closing_speed = 0.0;
+        self.lateral_speed = 0.0;
+        self.color_similarity = 0.0;
+    }
+
     /// Returns the distance from the object.
     fn get_distance(&self, body: &Body) -> f32 {
         match body.nshape.toi_with_ray(&body.isometry, &self.ray, true) {
@@ -161,16 +255,29 @@ impl Sensor {
     /// Build the output of the Sensor that correspond to its state. It's use to feed the A.I.
     /// engine.
     fn build_output(&mut self) {
-        let mut start_idx: usize = 0;
-
-        // Indications about what we are looking at.
-        self.output[start_idx] = self.is_bullet;
-        start_idx += 1;
-        self.output[start_idx] = self.is_panda;
-        start_idx += 1;
-
+        self.output.clear();
+        // Indications about what we are looking at, the flags of the features not in play being
+        // left out.
+        self.output.extend_from_slice(&[self.is_bullet, self.is_panda]);
+        if self.channels.wall {
+            self.output.push(self.is_wall);
+        }
+        if self.channels.food {
+            self.output.push(self.is_food);
+        }
+        if self.channels.ally {
+            self.output.push(self.is_ally);
+        }
         // Information on the whereabouts of the closest object we are looking at.
-        self.output[start_idx] = self.distance;
+        self.output.push(self.distance);
+
+        if self.kin_channels {
+            self.output.extend_from_slice(&[
+                self.relative_heading,
+                self.closing_speed,
+                self.lateral_speed,
+                self.color_similarity,
+            ]);
+        }
     }
 }
-
@@ -0,0 +1,87 @@
+//! The arena is the rectangle of the world where the pandas live and fight.
+//! Its size is independent from the window it's rendered in.
+
+use ggez::graphics::Point2;
+use na;
+use obstacles::Obstacles;
+use rand::Rng;
+
+// Default width of the arena in world units.
+pub const ARENA_WIDTH: f32 = 960.0;
+// Default height of the arena in world units.
+pub const ARENA_HEIGHT: f32 = 540.0;
+// How many random positions are tried before giving up on finding a spot clear of the obstacles.
+const CLEAR_POSITION_ATTEMPTS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Arena {
+    // The width of the arena.
+    pub width: f32,
+    // The height of the arena.
+    pub height: f32,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena::new(ARENA_WIDTH, ARENA_HEIGHT)
+    }
+}
+
+impl Arena {
+    /// Returns a new arena of the given size.
+    pub fn new(width: f32, height: f32) -> Self {
+        Arena { width, height }
+    }
+
+    /// Returns a random position inside the arena, used to spawn our actors.
+    pub fn random_position<R: Rng>(&self, rng: &mut R) -> Point2 {
+        Point2::new(
+            rng.gen_range(0.0, self.width) as f32,
+            rng.gen_range(0.0, self.height) as f32,
+        )
+    }
+
+    /// Returns a random position inside the arena where a disc of the given radius doesn't
+    /// overlap any obstacle, used to spawn our actors.
+    pub fn random_clear_position<R: Rng>(
+        &self,
+        obstacles: &Obstacles,
+        radius: f32,
+        rng: &mut R,
+    ) -> Point2 {
+        if obstacles.is_empty() {
+            return self.random_position(rng);
+        }
+
+        for _ in 0..CLEAR_POSITION_ATTEMPTS {
+            let pos = self.random_position(rng);
+            if !obstacles.overlaps(na::Point2::new(pos.x, pos.y), radius) {
+                return pos;
+            }
+        }
+
+        // The obstacles leave almost no room, so the last spot is pushed out of them instead.
+        let pos = self.random_position(rng);
+        let shift = obstacles.push_out(na::Point2::new(pos.x, pos.y), radius);
+        Point2::new(pos.x + shift.x, pos.y + shift.y)
+    }
+
+    /// Tells us if a position lies inside the arena.
+    pub fn contains(&self, pos: &Point2) -> bool {
+        0.0 <= pos.x && pos.x <= self.width && 0.0 <= pos.y && pos.y <= self.height
+    }
+
+    /// Returns the size of the torus the positions wrap around in a toroidal world. The actors
+    /// can go `margin / 2` past an edge before reappearing on the other side, so the torus is
+    /// `margin` larger than the arena.
+    pub fn torus_size(&self, margin: f32) -> (f32, f32) {
+        (self.width + margin, self.height + margin)
+    }
+
+    /// Returns the factor to apply to the arena so it fits in a window of the given size while
+    /// keeping its aspect ratio.
+    pub fn scale_to_fit(&self, window_width: f32, window_height: f32) -> f32 {
+        (window_width / self.width).min(window_height / self.height)
+    }
+}
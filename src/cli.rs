@@ -0,0 +1,224 @@
+//! Command line interface of the game binary.
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use std::str::FromStr;
+
+// Default number of pandas on the gameboard.
+const DEFAULT_ACTOR_NUMBER: &str = "32";
+// Default directory where the populations are saved.
+const DEFAULT_SAVE_DIR: &str = "tmp/save/";
+// Default directory where the brains are rendered.
+const DEFAULT_RENDER_DIR: &str = "tmp/";
+// Default configuration file of the window.
+const DEFAULT_WINDOW_CONF: &str = "resources/conf.toml";
+// Default configuration file of the simulation.
+const DEFAULT_SIM_CONF: &str = "resources/sim.toml";
+
+/// What the binary has been asked to do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    // Open a window and watch the pandas evolve.
+    Watch,
+    // Evolve the population without any window.
+    Train,
+    // Render the brains of a saved population to dot/svg files.
+    RenderBrains {
+        save_file: String,
+        output_dir: String,
+    },
+}
+
+/// Everything the user can set from the command line.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub command: Command,
+    // Number of pandas on the gameboard.
+    pub actor_number: usize,
+    // Wrap the world like a torus or confine the pandas inside its walls.
+    pub wrap_world: bool,
+    // Where the populations are saved.
+    pub save_dir: String,
+    // A save file to resume the game from.
+    pub load_file: Option<String>,
+    // The seed of the run, a random one is picked if none is given.
+    pub seed: Option<u64>,
+    // Number of generations to evolve before stopping, endless if none is given.
+    pub generation_limit: Option<usize>,
+    // The configuration file of the window.
+    pub window_conf: String,
+    // The configuration file of the simulation.
+    pub sim_conf: String,
+    // The scenario file laying out the obstacles of the arena, if any.
+    pub scenario: Option<String>,
+}
+
+/// Builds the command line parser.
+fn app<'a, 'b>() -> App<'a, 'b> {
+    App::new("wriggling-pandas")
+        .version(crate_version!())
+        .about("Pandas fighting in an arena, driven by an evolving artificial neural network.")
+        .setting(AppSettings::VersionlessSubcommands)
+        .arg(
+            Arg::with_name("actors")
+                .long("actors")
+                .short("a")
+                .value_name("NUMBER")
+                .default_value(DEFAULT_ACTOR_NUMBER)
+                .validator(|value| match value.parse::<usize>() {
+                    Ok(number) if number > 0 => Ok(()),
+                    _ => Err(format!("There must be at least one panda, got '{}'", value)),
+                })
+                .global(true)
+                .help("Number of pandas on the gameboard"),
+        )
+        .arg(
+            Arg::with_name("world")
+                .long("world")
+                .value_name("MODE")
+                .possible_values(&["wrap", "confine"])
+                .default_value("wrap")
+                .global(true)
+                .help("Wrap the world like a torus or confine the pandas inside its walls"),
+        )
+        .arg(
+            Arg::with_name("save-dir")
+                .long("save-dir")
+                .value_name("DIR")
+                .default_value(DEFAULT_SAVE_DIR)
+                .global(true)
+                .help("Directory where the populations are saved"),
+        )
+        .arg(
+            Arg::with_name("load")
+                .long("load")
+                .short("l")
+                .value_name("SAVE_FILE")
+                .global(true)
+                .help("Resume the game from a saved population"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .value_name("SEED")
+                .global(true)
+                .help("Seed driving every random draw of the game, but the brain mutations"),
+        )
+        .arg(
+            Arg::with_name("headless")
+                .long("headless")
+                .global(true)
+                .help("Run without any window, same as the 'train' subcommand"),
+        )
+        .arg(
+            Arg::with_name("generations")
+                .long("generations")
+                .short("g")
+                .value_name("NUMBER")
+                .global(true)
+                .help("Number of generations to evolve before stopping"),
+        )
+        .arg(
+            Arg::with_name("window-conf")
+                .long("window-conf")
+                .value_name("FILE")
+                .default_value(DEFAULT_WINDOW_CONF)
+                .global(true)
+                .help("Configuration file of the window"),
+        )
+        .arg(
+            Arg::with_name("sim-conf")
+                .long("sim-conf")
+                .value_name("FILE")
+                .default_value(DEFAULT_SIM_CONF)
+                .global(true)
+                .help("Configuration file of the simulation"),
+        )
+        .arg(
+            Arg::with_name("scenario")
+                .long("scenario")
+                .value_name("FILE")
+                .global(true)
+                .help("Scenario file laying out the obstacles of the arena"),
+        )
+        .subcommand(SubCommand::with_name("watch").about("Watch the pandas evolve in a window (default)"))
+        .subcommand(SubCommand::with_name("train").about("Evolve the pandas without any window"))
+        .subcommand(
+            SubCommand::with_name("render-brains")
+                .about("Render the brains of a saved population to dot/svg files")
+                .arg(
+                    Arg::with_name("SAVE_FILE")
+                        .required(true)
+                        .help("The saved population to render"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .short("o")
+                        .value_name("DIR")
+                        .default_value(DEFAULT_RENDER_DIR)
+                        .help("Directory where the brains are rendered"),
+                ),
+        )
+}
+
+/// Parses the value of an argument, exiting with a clap error if it can't be parsed.
+fn parse_value<T: FromStr>(matches: &ArgMatches, name: &str) -> Option<T> {
+    matches.value_of(name).map(|value| {
+        value.parse().unwrap_or_else(|_| {
+            ::clap::Error::value_validation_auto(format!(
+                "The value '{}' of '--{}' is invalid",
+                value, name
+            )).exit()
+        })
+    })
+}
+
+impl Options {
+    /// Returns the options parsed from the command line of the process.
+    pub fn from_args() -> Self {
+        let matches = app().get_matches();
+
+        // The global arguments are propagated down to the subcommand, if any.
+        let (command, sub_matches) = match matches.subcommand() {
+            ("train", Some(m)) => (Command::Train, m),
+            ("render-brains", Some(m)) => (
+                Command::RenderBrains {
+                    save_file: m.value_of("SAVE_FILE").unwrap_or("").to_string(),
+                    output_dir: m.value_of("output").unwrap_or(DEFAULT_RENDER_DIR).to_string(),
+                },
+                m,
+            ),
+            ("watch", Some(m)) => (Command::Watch, m),
+            _ => (Command::Watch, &matches),
+        };
+
+        let command = if command == Command::Watch && sub_matches.is_present("headless") {
+            Command::Train
+        } else {
+            command
+        };
+
+        Options {
+            command,
+            actor_number: parse_value(sub_matches, "actors")
+                .unwrap_or_else(|| DEFAULT_ACTOR_NUMBER.parse().unwrap()),
+            wrap_world: sub_matches.value_of("world") != Some("confine"),
+            save_dir: sub_matches
+                .value_of("save-dir")
+                .unwrap_or(DEFAULT_SAVE_DIR)
+                .to_string(),
+            load_file: sub_matches.value_of("load").map(|f| f.to_string()),
+            seed: parse_value(sub_matches, "seed"),
+            generation_limit: parse_value(sub_matches, "generations"),
+            window_conf: sub_matches
+                .value_of("window-conf")
+                .unwrap_or(DEFAULT_WINDOW_CONF)
+                .to_string(),
+            sim_conf: sub_matches
+                .value_of("sim-conf")
+                .unwrap_or(DEFAULT_SIM_CONF)
+                .to_string(),
+            scenario: sub_matches.value_of("scenario").map(|f| f.to_string()),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Hit detection between the bullets and the pandas, and contact detection between the pandas and
+//! with the food.
+//! A uniform grid is used as a broad phase so the exact tests only run on nearby pairs, and the
+//! tests check it against the brute-force version of `collisions_reference` testing every pair.
+
+use actors::{Body, BodyGrid, Bullet, Food, Panda};
+use ggez::graphics::Vector2;
+
+/// A bullet coming in contact with a living panda during the last tick.
+#[derive(Debug, Clone, Copy)]
+pub struct Hit {
+    // The index of the panda.
+    pub panda: usize,
+    // The index of the bullet.
+    pub bullet: usize,
+    // When the bullet first touched the panda, as a fraction of the last tick.
+    pub toi: f32,
+}
+
+/// Sorts the hits in the order they happened, the ties being broken by bullet then by panda,
+/// and only keeps the first contact between a bullet and a panda.
+pub fn sort_hits(hits: &mut Vec<Hit>) {
+    hits.sort_by(|a, b| {
+        (a.bullet, a.panda)
+            .cmp(&(b.bullet, b.panda))
+            .then(a.toi.partial_cmp(&b.toi).unwrap())
+    });
+    hits.dedup_by_key(|hit| (hit.bullet, hit.panda));
+    hits.sort_by(|a, b| {
+        a.toi
+            .partial_cmp(&b.toi)
+            .unwrap()
+            .then((a.bullet, a.panda).cmp(&(b.bullet, b.panda)))
+    });
+}
+
+/// Returns every contact between a bullet and a living panda during the last tick, in the order
+/// they happened, by only testing the pairs sharing a cell of a grid. A bullet is only stopped by
+/// the first panda it hits, which is up to the caller. In a toroidal world of the given size, the
+/// pandas near the seams get ghosts on the other side so they can be hit across them, the pandas
+/// going up to half the `wrap_margin` past the edges.
+pub fn broad_phase_hits(
+    panda_vector: &[Panda],
+    bullet_vector: &[Bullet],
+    torus_size: Option<(f32, f32)>,
+    wrap_margin: f32,
+) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = Vec::new();
+    if panda_vector.is_empty() || bullet_vector.is_empty() {
+        return hits;
+    }
+
+    // The contacts are swept along the motions of the last tick, so a bullet can hit any panda
+    // within the distance both of them moved.
+    let max_panda_motion = panda_vector
+        .iter()
+        .map(|panda| panda.body.motion.norm())
+        .fold(0.0, f32::max);
+    let max_bullet_motion = bullet_vector
+        .iter()
+        .map(|bullet| bullet.body.motion.norm())
+        .fold(0.0, f32::max);
+
+    let body_vector: Vec<Body> = panda_vector.iter().map(|panda| panda.body.clone()).collect();
+    let grid = match torus_size {
+        Some(size) => {
+            // A bullet never goes further past an edge than a panda, half a wrap margin, so
+            // only the ghosts within a wrap margin of the edges, plus the largest distance swept
+            // during the tick, can be hit.
+            let reach = wrap_margin + max_panda_motion + max_bullet_motion;
+            BodyGrid::new_wrapped(body_vector, size, reach)
+        },
+        None => BodyGrid::new(body_vector),
+    };
+
+    let mut candidates: Vec<usize> = Vec::new();
+    for (bullet_idx, bullet) in bullet_vector.iter().enumerate() {
+        // The disc around the path of the bullet during the last tick.
+        let motion = bullet.body.motion;
+        let radius = bullet.nshape.radius() + motion.norm() / 2.0 + max_panda_motion;
+        grid.bodies_near(
+            bullet.pos.x - motion.x / 2.0,
+            bullet.pos.y - motion.y / 2.0,
+            radius,
+            &mut candidates,
+        );
+
+        for body_idx in &candidates {
+            let panda_idx = grid.origin(*body_idx);
+            let body = &grid.bodies()[*body_idx];
+            if !panda_vector[panda_idx].is_alive() || body.tag == bullet.tag {
+                continue;
+            }
+            if let Some(toi) = body.swept_contact(&bullet.body) {
+                hits.push(Hit {
+                    panda: panda_idx,
+                    bullet: bullet_idx,
+                    toi,
+                });
+            }
+        }
+    }
+
+    // The hits are resolved in this order, so it must not depend on the grid. A bullet touching
+    // both a panda and its ghost only counts once.
+    sort_hits(&mut hits);
+    hits
+}
+
+/// Returns the (panda index, food index) pairs of the food eaten during the last tick, sorted by
+/// food. A piece of food touched by several pandas goes to the first one. There is only a handful
+/// of food, so every pair is tested.
+pub fn food_pickups(
+    panda_vector: &[Panda],
+    food_vector: &[Food],
+    torus_size: Option<(f32, f32)>,
+) -> Vec<(usize, usize)> {
+    let mut pickups: Vec<(usize, usize)> = Vec::new();
+
+    for (food_idx, food) in food_vector.iter().enumerate() {
+        let eater = panda_vector.iter().position(|panda| {
+            let toi = match torus_size {
+                Some(size) => panda.body.swept_contact_wrapped(&food.body, size),
+                None => panda.body.swept_contact(&food.body),
+            };
+            panda.is_alive() && toi.is_some()
+        });
+
+        if let Some(panda_idx) = eater {
+            pickups.push((panda_idx, food_idx));
+        }
+    }
+
+    pickups
+}
+
+/// Two overlapping pandas.
+#[derive(Debug, Clone)]
+pub struct PandaContact {
+    // The index of the first panda, always lower than the one of the second panda.
+    pub first: usize,
+    // The index of the second panda.
+    pub second: usize,
+    // The unit vector pointing from the first panda to the second one.
+    pub normal: Vector2,
+    // How deep the pandas overlap.
+    pub penetration: f32,
+}
+
+/// Returns every pair of overlapping living pandas, sorted by first then by second panda. In a
+/// toroidal world of the given size, the pandas near the seams get ghosts on the other side so
+/// they can bump into each other across them, the pandas going up to half the `wrap_margin` past
+/// the edges.
+pub fn panda_contacts(
+    panda_vector: &[Panda],
+    torus_size: Option<(f32, f32)>,
+    wrap_margin: f32,
+) -> Vec<PandaContact> {
+    let mut contacts: Vec<PandaContact> = Vec::new();
+    if panda_vector.len() < 2 {
+        return contacts;
+    }
+
+    let body_vector: Vec<Body> = panda_vector.iter().map(|panda| panda.body.clone()).collect();
+    let grid = match torus_size {
+        Some(size) => {
+            // A panda never goes further past an edge than half a wrap margin.
+            BodyGrid::new_wrapped(body_vector, size, wrap_margin)
+        },
+        None => BodyGrid::new(body_vector),
+    };
+
+    let mut candidates: Vec<usize> = Vec::new();
+    for (first, panda) in panda_vector.iter().enumerate() {
+        if !panda.is_alive() {
+            continue;
+        }
+
+        let center = panda.body.isometry.translation.vector;
+        let radius = panda.nshape.radius();
+        grid.bodies_near(center.x, center.y, radius, &mut candidates);
+
+        for body_idx in &candidates {
+            let second = grid.origin(*body_idx);
+            if second <= first || !panda_vector[second].is_alive() {
+                continue;
+            }
+
+            let body = &grid.bodies()[*body_idx];
+            let delta = body.isometry.translation.vector - center;
+            let dist = delta.norm();
+            let penetration = radius + body.nshape.radius() - dist;
+            if penetration > 0.0 {
+                // Two pandas right on top of each other are pushed apart along an arbitrary
+                // direction.
+                let normal = if dist > 0.0 {
+                    Vector2::new(delta.x / dist, delta.y / dist)
+                } else {
+                    Vector2::new(1.0, 0.0)
+                };
+                contacts.push(PandaContact {
+                    first,
+                    second,
+                    normal,
+                    penetration,
+                });
+            }
+        }
+    }
+
+    // A panda overlapping both another panda and its ghost only bumps into it once.
+    contacts.sort_by_key(|contact| (contact.first, contact.second));
+    contacts.dedup_by_key(|contact| (contact.first, contact.second));
+    contacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arena::Arena;
+    use collisions_reference::{brute_force_hits, hit_pairs, random_board};
+    use config::ActorConfig;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // The time step of a tick in the game, see `gameboard::TICK_DT`.
+    const TICK_DT: f32 = 1.0 / 180.0;
+
+    #[test]
+    fn broad_phase_finds_the_brute_force_hits() {
+        let arena = Arena::default();
+        let config = ActorConfig::default();
+        let torus_size = arena.torus_size(config.wrap_margin());
+
+        for &dt in &[TICK_DT, 0.1] {
+            for &torus_size in &[None, Some(torus_size)] {
+                for seed in 0..20 {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let (panda_vector, bullet_vector) =
+                        random_board(&mut rng, &arena, &config, dt);
+
+                    assert_eq!(
+                        hit_pairs(broad_phase_hits(
+                            &panda_vector,
+                            &bullet_vector,
+                            torus_size,
+                            config.wrap_margin(),
+                        )),
+                        hit_pairs(brute_force_hits(&panda_vector, &bullet_vector, torus_size)),
+                        "Seed {}, dt {}, torus {:?}",
+                        seed,
+                        dt,
+                        torus_size
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! The brute-force hit detection the broad phase is checked and timed against, testing every
+//! pair of bullet and panda, and the random gameboards it runs on. It's only built for the tests
+//! and the benches.
+
+use actors::{Bullet, Panda};
+use arena::Arena;
+use collisions::{sort_hits, Hit};
+use color_picker::WHITE;
+use config::ActorConfig;
+use obstacles::Obstacles;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+// Number of pandas on each random gameboard.
+pub const PANDA_NUMBER: usize = 128;
+// Number of bullets on each random gameboard.
+pub const BULLET_NUMBER: usize = 512;
+
+/// Returns every contact between a bullet and a living panda during the last tick, in the
+/// order they happened, by testing every pair. In a toroidal world of the given size, the
+/// contacts across the seams are computed from the closest image of each bullet.
+pub fn brute_force_hits(
+    panda_vector: &[Panda],
+    bullet_vector: &[Bullet],
+    torus_size: Option<(f32, f32)>,
+) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = Vec::new();
+
+    for (panda_idx, panda) in panda_vector.iter().enumerate() {
+        if !panda.is_alive() {
+            continue;
+        }
+
+        for (bullet_idx, bullet) in bullet_vector.iter().enumerate() {
+            if panda.tag == bullet.tag {
+                continue;
+            }
+
+            let toi = match torus_size {
+                Some(size) => panda.body.swept_contact_wrapped(&bullet.body, size),
+                None => panda.body.swept_contact(&bullet.body),
+            };
+            if let Some(toi) = toi {
+                hits.push(Hit {
+                    panda: panda_idx,
+                    bullet: bullet_idx,
+                    toi,
+                });
+            }
+        }
+    }
+
+    sort_hits(&mut hits);
+    hits
+}
+
+/// Returns the (bullet index, panda index) pairs of the hits, in the order of a brute-force
+/// search, and the first panda each bullet hits. The time of impact of a ghost may differ by
+/// a rounding error, so only the pairs are compared.
+pub fn hit_pairs(mut hits: Vec<Hit>) -> (Vec<(usize, usize)>, Vec<(usize, usize)>) {
+    let mut first_hits: Vec<(usize, usize)> = Vec::new();
+    for hit in &hits {
+        if !first_hits.iter().any(|&(bullet, _)| bullet == hit.bullet) {
+            first_hits.push((hit.bullet, hit.panda));
+        }
+    }
+    first_hits.sort_unstable();
+
+    hits.sort_by_key(|hit| (hit.bullet, hit.panda));
+    let pairs = hits.iter().map(|hit| (hit.bullet, hit.panda)).collect();
+    (pairs, first_hits)
+}
+
+/// Returns a random gameboard whose bullets flew for one tick of `dt` seconds, so their
+/// contacts are swept.
+pub fn random_board(
+    rng: &mut StdRng,
+    arena: &Arena,
+    config: &ActorConfig,
+    dt: f32,
+) -> (Vec<Panda>, Vec<Bullet>) {
+    // The hits are searched in an empty arena.
+    let obstacles = Obstacles::default();
+    let panda_vector: Vec<Panda> = (0..PANDA_NUMBER)
+        .map(|tag| Panda::new(tag, WHITE, arena, &obstacles, config, rng))
+        .collect();
+
+    let bullet_vector: Vec<Bullet> = (0..BULLET_NUMBER)
+        .map(|_| {
+            let tag = rng.gen_range(0, PANDA_NUMBER);
+            let pos = arena.random_position(rng);
+            let facing = rng.gen_range(0.0, 2.0 * ::std::f32::consts::PI);
+            let mut bullet = Bullet::new(tag, None, pos, facing, &WHITE, config);
+            bullet
+                .update(arena, &obstacles, config, dt)
+                .expect("Fail to update a bullet.");
+            bullet
+        })
+        .collect();
+
+    (panda_vector, bullet_vector)
+}
@@ -4,7 +4,7 @@ pub const BLACK: [f32; 4] = [0.23, 0.23, 0.23, 1.0];
 // pub const BLACK1: [f32; 4] = [0.2, 0.25, 0.28, 1.0];
 pub const BLACK2: [f32; 4] = [0.25, 0.34, 0.36, 1.0];
 pub const BLUE: [f32; 4] = [0.58, 0.73, 1.0, 1.0];
-// pub const GRAY: [f32; 4] = [0.75, 0.84, 0.87, 1.0];
+pub const GRAY: [f32; 4] = [0.75, 0.84, 0.87, 1.0];
 pub const GREEN: [f32; 4] = [0.8, 0.92, 0.62, 1.0];
 pub const ORANGE: [f32; 4] = [0.98, 0.62, 0.5, 1.0];
 pub const PURPLE: [f32; 4] = [0.83, 0.65, 0.93, 1.0];
@@ -0,0 +1,690 @@
+//! The simulation configuration, loaded from a toml file at startup so experiments are config
+//! changes instead of recompiles.
+
+use actors::{SENSOR_MAX_ANGLE, SENSOR_MIN_ANGLE};
+use arena::Arena;
+use std::fs::File;
+use std::io::Read;
+use toml;
+
+/// Everything tunable in the simulation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimConfig {
+    pub arena: Arena,
+    pub actors: ActorConfig,
+    pub food: FoodConfig,
+    pub teams: TeamConfig,
+    pub evolution: EvolutionConfig,
+}
+
+/// How our pandas and their bullets move, see and shoot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActorConfig {
+    // The area around a panda that trigger some score.
+    pub hitbox_size: f32,
+    // The maximum velocity our stuff can reach.
+    pub max_physics_vel: f32,
+    // Acceleration in pixels per second.
+    pub actor_thrust: f32,
+    // Rotation in radians per second.
+    pub actor_turn_rate: f32,
+    // Turn rate of the sensor, it also define how close the sensor can get with each other.
+    pub sensor_turn_rate: f32,
+    // The distance a Sensor / eye can 'see', unless the rig says otherwise.
+    pub sensor_max_dist: f32,
+    // Time to wait between 2 shots in second.
+    pub shooting_cooldown: f32,
+    // Bullet speed factor.
+    pub bullet_speed_factor: f32,
+    // The distance a bullet can travel before vanishing, endless if none is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bullet_max_range: Option<f32>,
+    // Time in second a bullet lives before vanishing, endless if none is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bullet_time_to_live: Option<f32>,
+    // What happens to a bullet reaching an edge of the arena.
+    pub bullet_edge: BulletEdge,
+    // Pandas bump into each other instead of passing through.
+    pub body_collisions: bool,
+    // Share of the velocity kept when two pandas bump into each other, from 0 (they stick
+    // together) to 1 (perfectly elastic).
+    pub collision_elasticity: f32,
+    // The hit points of a panda when it's born.
+    pub max_health: f32,
+    // Hit points lost when shot, a panda dies when it has none left. Pandas never die if 0.
+    pub bullet_damage: f32,
+    // Time in second a dead panda waits before respawning, dead for the rest of the round if
+    // none is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub respawn_delay: Option<f32>,
+    // Each sensor also tells about the kin of the panda or bullet it sees: its heading and
+    // velocity relative to the sensor, and how close its color is to ours.
+    pub kin_channels: bool,
+    // How the output of the brains is turned into orders. A loaded population keeps the one it
+    // was saved with.
+    pub decoder: DecoderKind,
+    // Every input of the brains is mapped to a documented range, see `InputNormalizer`. A loaded
+    // population keeps the choice it was saved with.
+    pub normalize_inputs: bool,
+    // The optional inputs in play, which aren't read from the file but derived from the rest of
+    // the configuration and from the scenario, see `SimConfig::input_channels`.
+    #[serde(skip)]
+    pub channels: InputChannels,
+    // How a panda moves and turns under the orders of its brain.
+    pub movement: MovementConfig,
+    // The optional orders a panda can receive from its brain.
+    pub actuators: ActuatorConfig,
+    // What a panda feels about its own state, on top of its health when the bullets hurt.
+    pub proprioception: ProprioceptionConfig,
+    // The rig of sensors each panda is fitted with. This one must stay the last field, as the
+    // toml format wants the arrays of tables after the plain values.
+    pub sensors: Vec<SensorConfig>,
+}
+
+/// One sensor of the rig of a panda.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorConfig {
+    // The angle in radians between the sensor and the facing of the panda at rest, positive on
+    // its left.
+    pub offset: f32,
+    // The distance this sensor can 'see', the 'sensor_max_dist' of the actors if none is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<f32>,
+}
+
+/// How the output of the brains is turned into orders.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecoderKind {
+    // Each axis is the difference of the absolute values of 2 outputs.
+    Differential,
+    // Each axis is a single output saturated by a tanh, within -1 and 1.
+    Tanh,
+    // Each output stands for one action, only the highest one is taken.
+    Argmax,
+}
+
+impl Default for DecoderKind {
+    fn default() -> Self {
+        DecoderKind::Differential
+    }
+}
+
+/// How a panda moves and turns under the orders of its brain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovementConfig {
+    // The movement model, the other values being only used by the momentum one.
+    pub model: MovementModel,
+    // The mass of a panda, the heavier the slower it gets up to speed and turns.
+    pub mass: f32,
+    // Share of the velocity lost per second.
+    pub linear_drag: f32,
+    // Share of the rotation speed lost per second, strictly positive as the torque is scaled by
+    // it so the top rotation speed is the turn rate for a mass of 1.
+    pub angular_drag: f32,
+    // Share of the thrust used when going backward.
+    pub reverse_thrust: f32,
+}
+
+/// The movement model of the pandas.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MovementModel {
+    // A panda turns at the turn rate and stops as soon as it stops thrusting.
+    Instant,
+    // A panda gets up to speed and slows down under the drags, and keeps turning for a while.
+    // With a mass of 1, the turn rate is its top rotation speed.
+    Momentum,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        MovementConfig {
+            model: MovementModel::Instant,
+            mass: 1.0,
+            linear_drag: 2.0,
+            angular_drag: 4.0,
+            reverse_thrust: 0.5,
+        }
+    }
+}
+
+/// The optional orders a panda can receive from its brain, on top of the thrust, the turn, the
+/// FOV and the fire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActuatorConfig {
+    // A panda can move sideway.
+    pub strafe: bool,
+    // Share of the thrust used when moving sideway.
+    pub strafe_thrust: f32,
+    // A panda can open or close its left and right sensors on their own.
+    pub sensor_control: bool,
+    // The closest and the furthest the sensors on the left can get from the facing, in radians.
+    pub left_sensor_angles: [f32; 2],
+    // The closest and the furthest the sensors on the right can get from the facing, in radians.
+    pub right_sensor_angles: [f32; 2],
+    // A panda can brake.
+    pub brake: bool,
+    // Share of the velocity and the rotation speed lost per second when braking.
+    pub brake_drag: f32,
+}
+
+impl Default for ActuatorConfig {
+    fn default() -> Self {
+        ActuatorConfig {
+            strafe: false,
+            strafe_thrust: 0.5,
+            sensor_control: false,
+            left_sensor_angles: [SENSOR_MIN_ANGLE, SENSOR_MAX_ANGLE],
+            right_sensor_angles: [SENSOR_MIN_ANGLE, SENSOR_MAX_ANGLE],
+            brake: false,
+            brake_drag: 8.0,
+        }
+    }
+}
+
+impl ActuatorConfig {
+    /// Returns the number of outputs needed by the actuators switched on, with 2 outputs per
+    /// axis like the differential decoder.
+    pub fn output_len(&self) -> usize {
+        let mut len = 0;
+        if self.strafe {
+            len += 2;
+        }
+        if self.sensor_control {
+            len += 4;
+        }
+        if self.brake {
+            len += 1;
+        }
+        len
+    }
+
+    /// Returns the angle limits of the sensors on the side of the given offset.
+    pub fn sensor_angles(&self, offset: f32) -> [f32; 2] {
+        if offset > 0.0 {
+            self.left_sensor_angles
+        } else {
+            self.right_sensor_angles
+        }
+    }
+}
+
+/// The inputs telling a panda about its own state, each one on or off.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProprioceptionConfig {
+    // Its velocity along its facing and toward its left, in shares of the max velocity.
+    pub velocity: bool,
+    // Its rotation speed, in shares of the turn rate.
+    pub angular_velocity: bool,
+    // The sine and the cosine of its facing.
+    pub heading: bool,
+    // Its distances to the left, right, top and bottom edges of the arena, in shares of the
+    // size of the arena. They stay at 1 in a toroidal world, which has no walls.
+    pub walls: bool,
+}
+
+impl ProprioceptionConfig {
+    /// Returns the number of inputs switched on.
+    pub fn input_len(&self) -> usize {
+        let mut len = 0;
+        if self.velocity {
+            len += 2;
+        }
+        if self.angular_velocity {
+            len += 1;
+        }
+        if self.heading {
+            len += 2;
+        }
+        if self.walls {
+            len += 4;
+        }
+        len
+    }
+}
+
+/// The inputs only fed to the brains when the feature they tell about is in play, each one on
+/// or off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputChannels {
+    // Each sensor tells if it sees an obstacle, when the scenario has some.
+    pub wall: bool,
+    // Each sensor tells if it sees some food, when there is some.
+    pub food: bool,
+    // Each sensor tells if it sees an ally, in the team mode.
+    pub ally: bool,
+    // A panda feels its own health, when the bullets hurt.
+    pub health: bool,
+}
+
+impl InputChannels {
+    /// Returns the number of inputs switched on for each sensor.
+    pub fn sensor_len(&self) -> usize {
+        self.wall as usize + self.food as usize + self.ally as usize
+    }
+}
+
+/// What happens to a bullet reaching an edge of the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulletEdge {
+    // It goes across the edge and reappears on the other side, like the pandas of a toroidal
+    // world. Only allowed in a toroidal world.
+    Wrap,
+    // It's removed from the gameboard.
+    Disappear,
+    // It bounces back into the arena.
+    Bounce,
+}
+
+impl Default for ActorConfig {
+    fn default() -> Self {
+        ActorConfig {
+            hitbox_size: 5.0,
+            max_physics_vel: 250.0,
+            actor_thrust: 2000.0,
+            actor_turn_rate: 1.0,
+            sensor_turn_rate: 1.0,
+            sensor_max_dist: 250.0,
+            shooting_cooldown: 1.0,
+            bullet_speed_factor: 3.0,
+            bullet_max_range: None,
+            bullet_time_to_live: None,
+            bullet_edge: BulletEdge::Disappear,
+            body_collisions: false,
+            collision_elasticity: 0.5,
+            max_health: 100.0,
+            bullet_damage: 0.0,
+            respawn_delay: None,
+            kin_channels: false,
+            decoder: DecoderKind::Differential,
+            normalize_inputs: false,
+            channels: InputChannels::default(),
+            movement: MovementConfig::default(),
+            actuators: ActuatorConfig::default(),
+            proprioception: ProprioceptionConfig::default(),
+            // A "virtual" left eye and a right one.
+            sensors: vec![
+                SensorConfig { offset: 0.1, range: None },
+                SensorConfig { offset: -0.1, range: None },
+            ],
+        }
+    }
+}
+
+impl ActorConfig {
+    /// The radius of the hitbox.
+    pub fn hitbox_radius(&self) -> f32 {
+        self.hitbox_size / 2.0
+    }
+
+    /// The radius of a bullet hitbox.
+    pub fn bullet_radius(&self) -> f32 {
+        self.hitbox_radius() / 1.5
+    }
+
+    /// The distance a sensor of the rig can 'see'.
+    pub fn sensor_range(&self, sensor: &SensorConfig) -> f32 {
+        sensor.range.unwrap_or(self.sensor_max_dist)
+    }
+
+    /// The longest distance any sensor of the rig can 'see'.
+    pub fn max_sensor_range(&self) -> f32 {
+        self.sensors
+            .iter()
+            .map(|sensor| self.sensor_range(sensor))
+            .fold(0.0, f32::max)
+    }
+
+    /// The widest FOV of the rig, with the sensors on each side opened up to their furthest
+    /// angle, see `Panda::update_fov_length`.
+    pub fn max_fov_length(&self) -> f32 {
+        let widest_on_side = |on_side: &dyn Fn(f32) -> bool| {
+            self.sensors
+                .iter()
+                .filter(|sensor| on_side(sensor.offset))
+                .map(|sensor| self.actuators.sensor_angles(sensor.offset)[1])
+                .fold(0.0, f32::max)
+        };
+        widest_on_side(&|offset| offset > 0.0) + widest_on_side(&|offset| offset < 0.0)
+    }
+
+    /// How far past the edges of the arena a panda goes before being wrapped around, see
+    /// `Panda::wrap_position`.
+    pub fn wrap_margin(&self) -> f32 {
+        self.hitbox_size * 2.0
+    }
+}
+
+/// The food lying around the arena, giving points or hit points to the pandas picking it up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoodConfig {
+    // Number of pieces of food in the arena, a piece being respawned elsewhere once eaten.
+    pub count: usize,
+    // The radius of a piece of food.
+    pub radius: f32,
+    // Number of score point won when eating a piece of food.
+    pub points: f32,
+    // Hit points restored when eating a piece of food, up to the max health.
+    pub health: f32,
+}
+
+impl Default for FoodConfig {
+    fn default() -> Self {
+        FoodConfig {
+            count: 0,
+            radius: 4.0,
+            points: 10.0,
+            health: 0.0,
+        }
+    }
+}
+
+/// The team game mode, where the pandas fight side by side instead of each one for itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TeamConfig {
+    // Number of teams, the pandas being dealt to each one in turn. Free-for-all if 0.
+    pub count: usize,
+    // What happens when a panda shoots one of its allies.
+    pub friendly_fire: FriendlyFire,
+    // Number of score point lost when shooting an ally with the penalised friendly fire.
+    pub friendly_fire_penalty: f32,
+    // Share of the fitness of a panda coming from the average score of its team, from 0 (only
+    // its own score counts) to 1 (only the team counts).
+    pub team_fitness_share: f32,
+}
+
+/// What happens when a panda shoots one of its allies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FriendlyFire {
+    // The bullets go through the allies.
+    Off,
+    // The allies are hit, and the shooter is penalised.
+    Penalised,
+    // The allies are hit like anyone else.
+    Allowed,
+}
+
+impl Default for TeamConfig {
+    fn default() -> Self {
+        TeamConfig {
+            count: 0,
+            friendly_fire: FriendlyFire::Allowed,
+            friendly_fire_penalty: 33.0,
+            team_fitness_share: 0.0,
+        }
+    }
+}
+
+impl TeamConfig {
+    /// Returns the team of a panda from its tag, if the team mode is on.
+    pub fn team_of(&self, tag: usize) -> Option<usize> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(tag % self.count)
+        }
+    }
+}
+
+/// How the A.I. engine evolves the population and scores each Panda.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvolutionConfig {
+    // Number of ticks before the next auto evolution pops.
+    pub countdown: usize,
+    // Every this value generation we trigger a structural mutation.
+    pub exploration_tick: usize,
+    // Number of score point win when a panda shot an other panda.
+    pub point_win_per_successful_shot: f32,
+    // Number of score point lost when shot.
+    pub point_lost_when_shot: f32,
+    // Number of score point won by both pandas for each tick they are in contact, a negative
+    // value being a penalty. Only used with the body collisions.
+    pub point_per_contact_tick: f32,
+    // Fitness won by a panda for each second it stays alive, on top of its score.
+    pub point_per_second_alive: f32,
+    // Probability for any mutation to apply on each specimen during exploration phase.
+    // Usually set between 0.05 and 0.1 (5 and 10 %).
+    pub mutation_probability: f32,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> Self {
+        EvolutionConfig {
+            countdown: 5_000,
+            exploration_tick: 50,
+            point_win_per_successful_shot: 33.0,
+            point_lost_when_shot: 77.0,
+            point_per_contact_tick: 0.0,
+            point_per_second_alive: 0.0,
+            mutation_probability: 0.05,
+        }
+    }
+}
+
+/// Returns an error if the value isn't strictly positive.
+fn check_positive(name: &str, value: f32) -> Result<(), String> {
+    if value > 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(format!("'{}' must be strictly positive, got {}.", name, value))
+    }
+}
+
+impl SimConfig {
+    /// Loads and validates the configuration from a toml file.
+    pub fn from_file(file_name: &str) -> Result<Self, String> {
+        let mut content = String::new();
+        File::open(file_name)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| format!("Fail to read '{}': {}", file_name, e))?;
+
+        let config: SimConfig = toml::from_str(&content)
+            .map_err(|e| format!("Fail to parse '{}': {}", file_name, e))?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Returns the optional inputs in play with this configuration, and with the obstacles of a
+    /// scenario if `has_obstacles`.
+    pub fn input_channels(&self, has_obstacles: bool) -> InputChannels {
+        InputChannels {
+            wall: has_obstacles,
+            food: self.food.count > 0,
+            ally: self.teams.count > 0,
+            health: self.actors.bullet_damage > 0.0,
+        }
+    }
+
+    /// Checks that the configuration can run in a toroidal world, if `wrap_world`, or in a
+    /// confined one.
+    pub fn validate_world(&self, wrap_world: bool) -> Result<(), String> {
+        // In a confined world, the collisions and the sensors can't see a bullet across the
+        // edges it wraps around.
+        if !wrap_world && self.actors.bullet_edge == BulletEdge::Wrap {
+            return Err(
+                "Bullets can only wrap around the edges of a 'wrap' world, set \
+                 'actors.bullet_edge' to \"disappear\" or \"bounce\"."
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that every value makes sense before running anything with it.
+    pub fn validate(&self) -> Result<(), String> {
+        check_positive("arena.width", self.arena.width)?;
+        check_positive("arena.height", self.arena.height)?;
+
+        let actors = &self.actors;
+        check_positive("actors.hitbox_size", actors.hitbox_size)?;
+        check_positive("actors.max_physics_vel", actors.max_physics_vel)?;
+        check_positive("actors.actor_thrust", actors.actor_thrust)?;
+        check_positive("actors.actor_turn_rate", actors.actor_turn_rate)?;
+        check_positive("actors.sensor_turn_rate", actors.sensor_turn_rate)?;
+        check_positive("actors.sensor_max_dist", actors.sensor_max_dist)?;
+        check_positive("actors.bullet_speed_factor", actors.bullet_speed_factor)?;
+        if !(actors.shooting_cooldown >= 0.0) {
+            return Err(format!(
+                "'actors.shooting_cooldown' must be positive, got {}.",
+                actors.shooting_cooldown
+            ));
+        }
+        if let Some(range) = actors.bullet_max_range {
+            check_positive("actors.bullet_max_range", range)?;
+        }
+        if let Some(time_to_live) = actors.bullet_time_to_live {
+            check_positive("actors.bullet_time_to_live", time_to_live)?;
+        }
+        // A bullet that never leaves the arena would stay there forever.
+        if actors.bullet_edge != BulletEdge::Disappear
+            && actors.bullet_max_range.is_none()
+            && actors.bullet_time_to_live.is_none()
+        {
+            return Err(format!(
+                "Bullets set to {:?} at the edges need an 'actors.bullet_max_range' or an \
+                 'actors.bullet_time_to_live'.",
+                actors.bullet_edge
+            ));
+        }
+
+        if !(0.0 <= actors.collision_elasticity && actors.collision_elasticity <= 1.0) {
+            return Err(format!(
+                "'actors.collision_elasticity' must be between 0 and 1, got {}.",
+                actors.collision_elasticity
+            ));
+        }
+
+        check_positive("actors.max_health", actors.max_health)?;
+        if !(actors.bullet_damage >= 0.0 && actors.bullet_damage.is_finite()) {
+            return Err(format!(
+                "'actors.bullet_damage' must be positive, got {}.",
+                actors.bullet_damage
+            ));
+        }
+        if let Some(delay) = actors.respawn_delay {
+            if !(delay >= 0.0 && delay.is_finite()) {
+                return Err(format!("'actors.respawn_delay' must be positive, got {}.", delay));
+            }
+        }
+
+        let movement = &actors.movement;
+        check_positive("actors.movement.mass", movement.mass)?;
+        // The torque of the momentum model is scaled by the angular drag, see
+        // `Panda::move_with_momentum`, so a panda without any can't turn.
+        check_positive("actors.movement.angular_drag", movement.angular_drag)?;
+        for &(name, value) in &[
+            ("actors.movement.linear_drag", movement.linear_drag),
+            ("actors.movement.reverse_thrust", movement.reverse_thrust),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(format!("'{}' must be positive, got {}.", name, value));
+            }
+        }
+
+        let actuators = &actors.actuators;
+        for &(name, value) in &[
+            ("actors.actuators.strafe_thrust", actuators.strafe_thrust),
+            ("actors.actuators.brake_drag", actuators.brake_drag),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(format!("'{}' must be positive, got {}.", name, value));
+            }
+        }
+        for &(name, angles) in &[
+            ("actors.actuators.left_sensor_angles", actuators.left_sensor_angles),
+            ("actors.actuators.right_sensor_angles", actuators.right_sensor_angles),
+        ] {
+            if !(0.0 <= angles[0] && angles[0] < angles[1] && angles[1] <= SENSOR_MAX_ANGLE) {
+                return Err(format!(
+                    "'{}' must be increasing and within 0 and {} radians, got {:?}.",
+                    name, SENSOR_MAX_ANGLE, angles
+                ));
+            }
+        }
+
+        for (idx, sensor) in actors.sensors.iter().enumerate() {
+            if !(sensor.offset.abs() < SENSOR_MAX_ANGLE) {
+                return Err(format!(
+                    "'actors.sensors[{}].offset' must be within +/-{} radians, got {}.",
+                    idx, SENSOR_MAX_ANGLE, sensor.offset
+                ));
+            }
+            check_positive(&format!("actors.sensors[{}].range", idx), actors.sensor_range(sensor))?;
+            // A sensor out of its angle limits would never move.
+            let angles = actuators.sensor_angles(sensor.offset);
+            if sensor.offset != 0.0
+                && !(angles[0] <= sensor.offset.abs() && sensor.offset.abs() <= angles[1])
+            {
+                return Err(format!(
+                    "'actors.sensors[{}].offset' must be within the sensor angles {:?}, got {}.",
+                    idx, angles, sensor.offset
+                ));
+            }
+        }
+
+        let food = &self.food;
+        check_positive("food.radius", food.radius)?;
+        if !food.points.is_finite() {
+            return Err("'food.points' must be finite.".to_string());
+        }
+        if !(food.health >= 0.0 && food.health.is_finite()) {
+            return Err(format!("'food.health' must be positive, got {}.", food.health));
+        }
+
+        let teams = &self.teams;
+        if !teams.friendly_fire_penalty.is_finite() {
+            return Err("'teams.friendly_fire_penalty' must be finite.".to_string());
+        }
+        if !(0.0 <= teams.team_fitness_share && teams.team_fitness_share <= 1.0) {
+            return Err(format!(
+                "'teams.team_fitness_share' must be between 0 and 1, got {}.",
+                teams.team_fitness_share
+            ));
+        }
+
+        let evolution = &self.evolution;
+        if evolution.countdown == 0 {
+            return Err("'evolution.countdown' must be at least 1.".to_string());
+        }
+        if evolution.exploration_tick == 0 {
+            return Err("'evolution.exploration_tick' must be at least 1.".to_string());
+        }
+        if !evolution.point_win_per_successful_shot.is_finite()
+            || !evolution.point_lost_when_shot.is_finite()
+        {
+            return Err("The points won and lost when shooting must be finite.".to_string());
+        }
+        if !evolution.point_per_contact_tick.is_finite() {
+            return Err("'evolution.point_per_contact_tick' must be finite.".to_string());
+        }
+        if !evolution.point_per_second_alive.is_finite() {
+            return Err("'evolution.point_per_second_alive' must be finite.".to_string());
+        }
+        if !(0.0 <= evolution.mutation_probability && evolution.mutation_probability <= 1.0) {
+            return Err(format!(
+                "'evolution.mutation_probability' must be between 0 and 1, got {}.",
+                evolution.mutation_probability
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the configuration as a toml string, mostly to log the config in use.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_else(|e| format!("{:?}", e))
+    }
+}
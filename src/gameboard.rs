@@ -1,67 +1,51 @@
 //! Hold our game state.
 
-use actors::{Body, Bullet, Panda};
 use color_picker::*;
 use gameboard_controller::InputState;
 use ggez::event::{self, Keycode, Mod};
 use ggez::{graphics, timer, Context, GameResult};
-use rayon::prelude::*;
-use fnv::FnvHashMap;
-
-use fluffy_penguin::genetic_algorithm::Specimen;
-use fluffy_penguin::genetic_algorithm::Population;
+use obstacles::{ObstacleShape, Obstacles};
+use simulation::Simulation;
+use sprites::ActorBatches;
+use std::time::{Duration, Instant};
 
 // The desired FPS (or speed so to speak) our world
 // will run at. It's destined to be modified by the user
 // later on.
 const DESIRED_FPS: u32 = 90;
-const SAVE_DIR: &str = "tmp/save/";
 // Game speed value.
-const GAME_SPEED: f32 = DESIRED_FPS as f32 * 2.0;
-// The step at which rate we want to modify the speed of the simulation at run time.
-const SPEED_STEP: f32 = 5.0;
-// This is the final contdown ! tududu du tududududu...
-// This is the remaining value before next auto evolution pop.
-// const COUNTDOWN: usize = 500;
-const COUNTDOWN: usize = 5_000;
-// Every this value tick we trigger a structural mutation.
-const EXPLORATION_TICK: usize = 50;
+pub const GAME_SPEED: f32 = DESIRED_FPS as f32 * 2.0;
+// The fixed time step of each simulation tick, whatever the time scale is, so speeding up the
+// game never changes its physics.
+pub const TICK_DT: f32 = 1.0 / GAME_SPEED;
+// The maximum number of simulation ticks run per update.
+const MAX_TIME_SCALE: usize = 256;
 // Font size of text that will be printed
 // on the screen to inform the user.
 const FONT_SIZE: u32 = 12;
-// Number of actor per board.
-const ACTOR_NUMBER_PER_BOARD: usize = 128;
-// Number of bullet maximum on a gameboard.
-const BULLET_NUMBER_PER_BOARD: usize = ACTOR_NUMBER_PER_BOARD * 4;
-// Number of score point win when a panda shot an other panda.
-const POINT_WIN_PER_SUCCESSFUL_SHOT: f32 = 33.0;
-// Number of score point lost when shot.
-const POINT_LOST_WHEN_SHOT: f32 = 77.0;
-// All the color a panda can wear.
-const COLOR_ARRAY: [[f32; 4]; 8] = [WHITE, AQUA, RED, GREEN, BLUE, ORANGE, PURPLE, YELLOW];
-// Probability for any mutation to apply on each specimen during exploration phase.
-// Usually set between 0.05 and 0.1 (5 and 10 %).
-// TODO: Make this configurable <08-08-18, dymayday> //
-const MUTATION_PROBABILITY: f32 = 0.05;
 
 /// This is our main state data handler.
 pub struct State {
     //text: graphics::Text,
     font: graphics::Font,
-    panda_vector: Vec<Panda>,
-    population: Population<f32>,
-    bullet_vector: Vec<Bullet>,
+    // The world itself: pandas, bullets and their brains.
+    simulation: Simulation,
     input: InputState,
-    speed: f32,
-    generation: usize,
-    countdown: usize,
-    wrap_world: bool,
-    save_dir: String,
+    // Number of simulation ticks run per update.
+    time_scale: usize,
+    // When set, we run as many ticks as the CPU allows and skip drawing the arena.
+    fast_forward: bool,
+    // The sprite batches every actor is queued in before being drawn.
+    batches: ActorBatches,
+    // The score texts of each panda along with the score they show, so a text is only rebuilt
+    // when the score changes.
+    score_texts: Vec<(f32, graphics::Text)>,
+    // The obstacles never move, so they are built in a single mesh once and for all.
+    obstacles_mesh: Option<graphics::Mesh>,
 }
 
 impl State {
-    pub fn new(ctx: &mut Context) -> GameResult<State> {
-        use actors::{AI_ENGINE_INPUT_LEN, AI_ENGINE_OUTPUT_LEN};
+    pub fn new(ctx: &mut Context, simulation: Simulation) -> GameResult<State> {
         // Let's set our background with a nice "blackish" color
         // from the material theme
         graphics::set_background_color(ctx, BLACK.into());
@@ -72,137 +56,58 @@ impl State {
         let font = graphics::Font::new(ctx, "/FiraSans-Regular.ttf", FONT_SIZE)?;
         //let text = graphics::Text::new(ctx, "Hello world!", &font)?;
 
-        // Create a iterator from which we can cycle through to give our pandas roughfly different
-        // colors.
-        let mut color_iter_cycle = COLOR_ARRAY.iter().cycle();
-
-        let mut panda_vector: Vec<Panda> = Vec::with_capacity(ACTOR_NUMBER_PER_BOARD);
-        for tag in 0..ACTOR_NUMBER_PER_BOARD {
-            let panda_color = color_iter_cycle
-                .next()
-                .expect("Fail to cycle through the available color.");
-            let mut panda = Panda::new(ctx, tag as usize, *panda_color);
-            panda_vector.push(panda);
-        }
-        // let panda_vector = State::new_actor_population(ctx)?;
-
-        let population_size: usize = ACTOR_NUMBER_PER_BOARD;
-        let input_size: usize = AI_ENGINE_INPUT_LEN;
-        let output_size: usize = AI_ENGINE_OUTPUT_LEN;
-        let mutation_probability: f32 = MUTATION_PROBABILITY;
-        let mut population: Population<f32> = Population::new(
-            population_size,
-            input_size,
-            output_size,
-            mutation_probability,
-        );
-        population
-            // .set_lambda((ACTOR_NUMBER_PER_BOARD / 2 ) as usize)
-            .set_s_rank(1.5);
-        population.exploration();
-
-        let bullet_vector: Vec<Bullet> = Vec::with_capacity(BULLET_NUMBER_PER_BOARD);
+        let batches = ActorBatches::new(ctx)?;
+        let obstacles_mesh = State::build_obstacles_mesh(ctx, simulation.obstacles())?;
 
         Ok(State {
             font,
-            panda_vector,
-            population,
-            bullet_vector,
+            simulation,
             input: InputState::default(),
-            speed: GAME_SPEED,
-            generation: 0,
-            countdown: COUNTDOWN,
-            wrap_world: true,
-            save_dir: SAVE_DIR.to_string(),
+            time_scale: 1,
+            fast_forward: false,
+            batches,
+            score_texts: Vec::new(),
+            obstacles_mesh,
         })
     }
 
-
-    /// Set the number of actor on the gameboard.
-    pub fn with_actor_capacity(mut self, actor_size: usize) -> Self {
-        // If there is enought actor it's easy, we just slice them from the original popilation.
-        if actor_size <= self.panda_vector.len() {
-            self.panda_vector = self.panda_vector[..actor_size].to_vec();
-        } else {
-            // But if there is not enough Panda to satisfy our appetite, we need to cycle through
-            // the ones we have in stock.
-            let mut new_panda_vector: Vec<Panda> = Vec::with_capacity(actor_size);
-            let mut panda_iter_cycle = self.panda_vector.into_iter().cycle();
-            for _ in 0..actor_size {
-                let panda = panda_iter_cycle
-                    .next()
-                    .expect("Fail to cycle through the vector of Panda.");
-
-                new_panda_vector.push(panda.to_owned());
-            }
-            self.panda_vector = new_panda_vector;
-        }
-
-        // Let's shrink the population size to fit the desired actor size.
-        self.population = self.population.shrink_to(actor_size);
-        self
-    }
-
-
-    /// Determines if the position of each Panda will be wrap in a toric world, or if they will be
-    /// stuck on the imaginary walls of the arena.
-    /// True by default.
-    pub fn wrap_world(mut self, b: bool) -> Self {
-        self.wrap_world = b;
-        self
-    }
-
-
-    /// Update the default game save directory.
-    pub fn set_save_directory(mut self, save_dir: &str) -> Self {
-        self.save_dir = save_dir.to_string();
-        self
-    }
-
-
-    /// Reset the population of Panda on the gameboard.
-    fn new_actor_population(ctx: &mut Context, actor_size: usize) -> GameResult<Vec<Panda>> {
-        // Create a iterator from which we can cycle through to give our pandas roughfly different
-        // colors.
-        let mut color_iter_cycle = COLOR_ARRAY.iter().cycle();
-
-        let mut panda_vector: Vec<Panda> = Vec::with_capacity(actor_size);
-        for tag in 0..actor_size {
-            let panda_color = color_iter_cycle
-                .next()
-                .expect("Fail to cycle through the available color.");
-            let mut panda = Panda::new(ctx, tag as usize, *panda_color);
-            panda_vector.push(panda);
+    /// Builds the mesh of all the obstacles of the arena, if there is any.
+    fn build_obstacles_mesh(
+        ctx: &mut Context,
+        obstacles: &Obstacles,
+    ) -> GameResult<Option<graphics::Mesh>> {
+        if obstacles.is_empty() {
+            return Ok(None);
         }
-        Ok(panda_vector)
-    }
 
-    /// This is where the collision between the pandas and the bullets are handled.
-    fn handle_collisions(&mut self) -> GameResult<()> {
-        // This HashMap let us update the score of panda that successfully shoot someone.
-        let mut successfull_panda_shot_hashmap: FnvHashMap<usize, f32> =
-            FnvHashMap::with_capacity_and_hasher(self.panda_vector.len(), Default::default());
-
-        for mut panda in &mut self.panda_vector {
-            for mut bullet in &mut self.bullet_vector {
-                if panda.tag != bullet.tag && panda.body.in_contact(&bullet.body) {
-                    panda.score -= POINT_LOST_WHEN_SHOT;
-                    bullet.to_remove = true;
-
-                    let score = successfull_panda_shot_hashmap
-                        .entry(bullet.tag)
-                        .or_insert(0.0);
-                    *score += POINT_WIN_PER_SUCCESSFUL_SHOT;
-                }
+        let mb = &mut graphics::MeshBuilder::new();
+        for shape in obstacles.shapes() {
+            match *shape {
+                ObstacleShape::Circle { ref center, radius } => {
+                    let center = graphics::Point2::new(center[0], center[1]);
+                    mb.circle(graphics::DrawMode::Fill, center, radius, 0.5);
+                },
+                ObstacleShape::Box { ref center, ref size } => {
+                    let (x, y) = (center[0] - size[0] / 2.0, center[1] - size[1] / 2.0);
+                    let corners = [
+                        graphics::Point2::new(x, y),
+                        graphics::Point2::new(x + size[0], y),
+                        graphics::Point2::new(x + size[0], y + size[1]),
+                        graphics::Point2::new(x, y + size[1]),
+                    ];
+                    mb.polygon(graphics::DrawMode::Fill, &corners);
+                },
+                ObstacleShape::Polyline { ref points } => {
+                    let points: Vec<graphics::Point2> = points
+                        .iter()
+                        .map(|point| graphics::Point2::new(point[0], point[1]))
+                        .collect();
+                    mb.line(&points, 2.0);
+                },
             }
         }
 
-        // Here we update the score of each Panda whose bullet hit a target.
-        for (tag, score) in &successfull_panda_shot_hashmap {
-            self.panda_vector[*tag].score += score;
-        }
-
-        Ok(())
+        Ok(Some(mb.build(ctx)?))
     }
 
     /// Print FPS to screen
@@ -223,15 +128,27 @@ impl State {
     fn draw_scores(&mut self, ctx: &mut Context) -> GameResult<()> {
         let mut y_pos = FONT_SIZE as f32 * 2.0;
 
+        // Print how fast the simulation is running.
+        let speed_string = if self.fast_forward {
+            "Speed : fast-forward".to_string()
+        } else {
+            format!("Speed : x{}", self.time_scale)
+        };
+        let speed = graphics::Text::new(ctx, &speed_string, &self.font)?;
+        let dest_point = graphics::Point2::new(10.0, y_pos);
+        graphics::set_color(ctx, WHITE.into())?;
+        graphics::draw(ctx, &speed, dest_point, 0.0)?;
+        y_pos += FONT_SIZE as f32 + 2.0;
+
         // Print the countdown before next evolution.
-        let countdown = graphics::Text::new(ctx, &format!("Countdown : {:>6}", self.countdown), &self.font)?;
+        let countdown = graphics::Text::new(ctx, &format!("Countdown : {:>6}", self.simulation.countdown), &self.font)?;
         let dest_point = graphics::Point2::new(10.0, y_pos);
         graphics::set_color(ctx, WHITE.into())?;
         graphics::draw(ctx, &countdown, dest_point, 0.0)?;
         y_pos += FONT_SIZE as f32 + 2.0;
 
         // Print the generation number we are currently at.
-        let generation = graphics::Text::new(ctx, &format!("Generation : {:>4}", self.generation), &self.font)?;
+        let generation = graphics::Text::new(ctx, &format!("Generation : {:>4}", self.simulation.generation), &self.font)?;
         let dest_point = graphics::Point2::new(10.0, y_pos);
         graphics::set_color(ctx, WHITE.into())?;
         graphics::draw(ctx, &generation, dest_point, 0.0)?;
@@ -245,25 +162,61 @@ impl State {
         graphics::draw(ctx, &gscore_header, dest_point, 0.0)?;
 
         y_pos += FONT_SIZE as f32 + 2.0;
-        for panda in &self.panda_vector {
-            let score_string = format!("{:3} : {:4}", panda.tag + 1, panda.score);
-            let gscore = graphics::Text::new(ctx, &score_string, &self.font)?;
+        let window_height = graphics::get_size(ctx).1 as f32;
+        self.score_texts.truncate(self.simulation.panda_vector.len());
+
+        for (idx, panda) in self.simulation.panda_vector.iter().enumerate() {
+            let dest_y = panda.tag as f32 + y_pos;
+            // There is no need to print the scores that don't fit in the window.
+            if dest_y > window_height {
+                break;
+            }
+
+            // The text is only rebuilt when the score it shows changed.
+            if idx >= self.score_texts.len() || self.score_texts[idx].0 != panda.score {
+                let score_string = format!("{:3} : {:4}", panda.tag + 1, panda.score);
+                let gscore = graphics::Text::new(ctx, &score_string, &self.font)?;
+                if idx < self.score_texts.len() {
+                    self.score_texts[idx] = (panda.score, gscore);
+                } else {
+                    self.score_texts.push((panda.score, gscore));
+                }
+            }
 
             // Drawables are drawn from their top-left corner.
-            let dest_point = graphics::Point2::new(10.0, panda.tag as f32 + y_pos);
+            let dest_point = graphics::Point2::new(10.0, dest_y);
             graphics::set_color(ctx, panda.color.into())?;
-            graphics::draw(ctx, &gscore, dest_point, 0.0)?;
+            graphics::draw(ctx, &self.score_texts[idx].1, dest_point, 0.0)?;
 
             y_pos += FONT_SIZE as f32;
         }
         Ok(())
     }
 
+    /// Draw the borders of the arena.
+    fn draw_arena(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let arena = *self.simulation.arena();
+        let rect = graphics::Rect::new(0.0, 0.0, arena.width, arena.height);
+
+        graphics::set_color(ctx, BLACK2.into())?;
+        graphics::rectangle(ctx, graphics::DrawMode::Line(1.0), rect)?;
+
+        if let Some(ref mesh) = self.obstacles_mesh {
+            let drawparam = graphics::DrawParam {
+                color: Some(GRAY.into()),
+                ..graphics::DrawParam::default()
+            };
+            graphics::draw_ex(ctx, mesh, drawparam)?;
+        }
+
+        Ok(())
+    }
+
     /// Draw the gameboard grid.
     fn _draw_grid(&mut self, ctx: &mut Context) -> GameResult<()> {
         let board_graticul_size: f32 = 10.0;
-        let height: f32 = ctx.conf.window_mode.height as f32;
-        let width: f32 = ctx.conf.window_mode.width as f32;
+        let height: f32 = self.simulation.arena().height;
+        let width: f32 = self.simulation.arena().width;
 
         let x_line_number = (width / board_graticul_size) as usize;
         let y_line_number = (height / board_graticul_size) as usize;
@@ -301,108 +254,6 @@ impl State {
 
         Ok(())
     }
-
-
-    /// Update the population from the A.I. engine.
-    fn evolve(&mut self, ctx: &mut Context) -> GameResult<()> {
-        // Let's keep track of how far we can get.
-        self.generation += 1;
-
-        // Update the fitness value of each Specimen with the score of its associated Panda.
-        for (mut panda, mut specimen) in &mut self.panda_vector.iter().zip(&mut self.population.species) {
-            specimen.fitness = panda.score;
-        }
-
-        // if self.generation % EXPLORATION_TICK == 0 {
-        //     let mut pop_sorted = self.population.clone();
-        //     pop_sorted.sort_species_by_fitness();
-        //
-        //     info!("Rendering Specimens...");
-        //     pop_sorted.render("tmp/sorted_vizualisation/", false, false);
-        //     self.save_to_file();
-        // }
-
-        // Evolve the population by mating them together.
-        self.population.evolve();
-
-        // self.population.render(&format!("tmp/vizualisation/gen_{:0>3}/", self.generation), false, false);
-
-        if self.generation % EXPLORATION_TICK == 0 {
-            info!("Generation {:>3} : Structural Exploration.", self.generation);
-            self.population.exploration();
-
-            info!("Rendering Specimens...");
-            self.population.render("tmp/vizualisation/", false, false);
-            self.save_to_file();
-        } else {
-            info!("Generation {:>3} : Parametric Exploitation.", self.generation);
-            self.population.exploitation();
-        }
-
-        self.reset_board(ctx)?;
-        Ok(())
-    }
-
-
-    /// Save the Panda's brains to file.
-    fn save_to_file(&self) {
-        use chrono::prelude::*;
-
-        let date = Local::now().format("%FT%Hh%Mm%Ss");
-        let file_name = format!("{}/{}_Population-gen{:03}.bc", SAVE_DIR, date, self.generation);
-        match self.population.save_to_file(&file_name) {
-            Ok(_) => info!("Saving Population to '{}'.", file_name),
-            Err(e) => warn!("Fail to save to '{}': {}", file_name, e),
-        };
-    }
-
-
-    /// Load the Panda's brains from file.
-    fn load_population_from_file(&mut self, file_name: &str) -> Result<(), ()> {
-        match fluffy_penguin::genetic_algorithm::Population::load_from_file(file_name) {
-            Ok(population) => {
-                self.generation = population.generation_counter;
-                self.population = population;
-                return Ok(())
-            },
-            Err(e) => {
-                crit!("{}", &format!("{:?}", e));
-                return Err(())
-            }
-        }
-    }
-
-
-    /// Load the Panda's brains from the last previous save file.
-    pub fn reload_population_from_last_saved_game(&mut self) {
-        use glob::glob;
-
-        let wild_card = &format!("{}/*.bc", SAVE_DIR);
-        let mut fpl: Vec<String> = glob(wild_card).expect("Failed to read glob pattern")
-            .filter_map(|p| Some(p.unwrap().to_str().unwrap().to_string()))
-            .collect::<Vec<String>>();
-        fpl.sort();
-        debug!("fpl = {:#?}", fpl);
-
-        let file_name = fpl.last().unwrap().to_owned();
-        match self.load_population_from_file(&file_name) {
-            Ok(_) => info!("Loading Game from '{}'.", file_name),
-            Err(_) => warn!("Fail to load the game from '{}'.", file_name),
-        };
-    }
-
-
-    /// Wipe clean the entire gameboard.
-    fn reset_board(&mut self, ctx: &mut Context) -> GameResult<()> {
-        self.panda_vector = State::new_actor_population(ctx, self.panda_vector.len())?;
-
-        // Clean all the bullets as well.
-        self.bullet_vector.clear();
-
-        self.countdown = COUNTDOWN;
-
-        Ok(())
-    }
 }
 
 impl event::EventHandler for State {
@@ -410,89 +261,39 @@ impl event::EventHandler for State {
 
     /// This is the update one.
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        // This function will return true if the time since the last update() call has been
-        // equal to or greater to the update FPS indicated by the DESIRED_FPS.
-        while timer::check_update_time(ctx, DESIRED_FPS) {
-            let dt: f32 = 1.0 / self.speed;
-
-            // Here we clean the gameboard from all unnecessary bullet.
-            let mut bullet_to_keep_vector: Vec<Bullet> =
-                Vec::with_capacity(self.bullet_vector.len());
+        if self.simulation.is_done() {
+            info!("Reached generation {}, stopping the game.", self.simulation.generation);
+            self.simulation.save_to_file();
+            ctx.quit()?;
+            return Ok(());
+        }
 
-            for mut bullet in &mut self.bullet_vector {
-                if !bullet.to_remove {
-                    bullet.update(ctx, dt)?;
-                    bullet_to_keep_vector.push(bullet.to_owned());
-                }
+        if self.fast_forward {
+            // We step the world for as long as a frame lasts, nothing is drawn anyway, but never
+            // past the generation limit.
+            let frame_budget = Duration::from_millis(1_000 / u64::from(DESIRED_FPS));
+            let start = Instant::now();
+            while start.elapsed() < frame_budget && !self.simulation.is_done() {
+                self.simulation.step(TICK_DT)?;
             }
-            self.bullet_vector = bullet_to_keep_vector;
-            {
-                // Here we build a vector containing all the object each panda can interact with: the
-                // other pandas and the bullets.
-                let cap: usize = self.panda_vector.len() + self.bullet_vector.len();
-                let mut body_vector: Vec<Body> = Vec::with_capacity(cap);
-                for panda in &self.panda_vector {
-                    body_vector.push(Body::new(panda.tag, false, &panda.nshape, &panda.isometry));
-                }
-
-                for bullet in &self.bullet_vector {
-                    body_vector.push(Body::new(bullet.tag, true, &bullet.nshape, &bullet.iso));
-                }
-
-                // Let's update all the pandas.
-                for i in 0..self.panda_vector.len() {
-                    let mut panda: &mut Panda = &mut self.panda_vector[i];
-                    let mut specimen: &mut Specimen<f32> = &mut self.population.species[i];
-
-                    // We manually update the input values we feed to the ANN.
-                    specimen.update_input(&panda.input_to_ai);
-                }
-
-
-                // Here we evaluate each specimen in parallel.
-                let mut input_state_v: Vec<InputState> = Vec::with_capacity(self.panda_vector.len());
-                self.population.species.par_iter_mut()
-                    .map(|specimen| {
-                        // Input commands computed by the ANN from the A.I. engine.
-                        Panda::build_input_from_ai(&specimen.evaluate())
-                    }).collect_into_vec(&mut input_state_v);
-
-                // // Un-parallelized version.
-                // let mut input_state_v: Vec<InputState> = self.population.species.iter_mut()
-                //     .map(|specimen| {
-                //         // Input commands computed by the ANN from the A.I. engine.
-                //         Panda::build_input_from_ai(&specimen.evaluate())
-                //     }).collect();
 
+            // Drain the timer so we don't try to catch up when leaving the fast-forward mode.
+            while timer::check_update_time(ctx, DESIRED_FPS) {}
+            return Ok(());
+        }
 
-                // Let's update all the pandas.
-                for i in 0..self.panda_vector.len() {
-                    let mut panda: &mut Panda = &mut self.panda_vector[i];
-
-                    // Input commands computed by the ANN from the A.I. engine.
-                    panda.handle_input(&input_state_v[i], &mut self.bullet_vector, dt);
-                    panda.update(ctx, &body_vector, self.wrap_world, dt)?;
+        // This function will return true if the time since the last update() call has been
+        // equal to or greater to the update FPS indicated by the DESIRED_FPS.
+        while timer::check_update_time(ctx, DESIRED_FPS) {
+            for _ in 0..self.time_scale {
+                // The generation limit is handled on the next update.
+                if self.simulation.is_done() {
+                    break;
                 }
-
-                // self.panda_vector[0].handle_input(&self.input, &mut self.bullet_vector, dt);
-                // self.panda_vector[0].update(ctx, &body_vector, dt)?;
-
-
-            }
-
-            {
-                self.handle_collisions()?;
+                self.simulation.step(TICK_DT)?;
             }
         }
 
-        // Run the countdown before next auto evolution triggers.
-        if self.countdown <= 0 {
-            self.evolve(ctx)?;
-            self.countdown = COUNTDOWN;
-        } else {
-            self.countdown -= 1;
-        }
-
         Ok(())
     }
 
@@ -500,18 +301,42 @@ impl event::EventHandler for State {
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
         graphics::clear(ctx);
 
+        // The arena lives in its own coordinates, so we scale it to fit whatever window is open.
+        let (window_width, window_height) = graphics::get_size(ctx);
+        let scale = self
+            .simulation
+            .arena()
+            .scale_to_fit(window_width as f32, window_height as f32);
+        graphics::push_transform(ctx, Some(graphics::Matrix4::new_scaling(scale)));
+        graphics::apply_transformations(ctx)?;
+
         // Draw the gameboard grid. Maybe there is a lazy implementation of the draw call but I
         // haven't found it yet.
         // self._draw_grid(ctx)?; // TODO: Uncomment this when we are in release mode.
+        self.draw_arena(ctx)?;
+
+        // Drawing the actors is skipped in fast-forward, it would only slow the evolution down.
+        if !self.fast_forward {
+            // Every actor is queued in the sprite batches, which are then drawn all at once.
+            self.batches.clear();
+            for food in &self.simulation.food_vector {
+                food.draw(&mut self.batches);
+            }
 
-        for mut panda in &mut self.panda_vector {
-            panda.draw(ctx)?;
-        }
+            for panda in self.simulation.panda_vector.iter().filter(|panda| panda.is_alive()) {
+                panda.draw(&mut self.batches);
+            }
 
-        for mut bullet in &mut self.bullet_vector {
-            bullet.draw(ctx)?;
+            for bullet in &self.simulation.bullet_vector {
+                bullet.draw(&mut self.batches);
+            }
+            self.batches.draw(ctx)?;
         }
 
+        // The text informations are drawn in window coordinates.
+        graphics::pop_transform(ctx);
+        graphics::apply_transformations(ctx)?;
+
         self.draw_fps(ctx)?;
         self.draw_scores(ctx)?;
 
@@ -555,8 +380,8 @@ impl event::EventHandler for State {
                 self.input.fov_axis = -1.0;
             }
             Keycode::L => {
-                self.reload_population_from_last_saved_game();
-                self.reset_board(ctx)
+                self.simulation.reload_population_from_last_saved_game();
+                self.simulation.reset_board()
                     .expect("Fail to reset the Gameboard after loading from last game's save.");
             }
             Keycode::Space => {
@@ -572,39 +397,46 @@ impl event::EventHandler for State {
             }
             Keycode::E => {
                 println!("Evolving...");
-                self.evolve(ctx).expect("Fail to evolve A.I. population.");
-                if self.generation % 5 == 0 {
+                self.simulation.evolve().expect("Fail to evolve A.I. population.");
+                if self.simulation.generation % 5 == 0 {
                     println!("Structural Exploration.");
-                    self.population.exploration();
+                    self.simulation.population.exploration();
                 } else {
                     println!("Parametric Exploitation.");
-                    self.population.exploitation();
+                    self.simulation.population.exploitation();
                 }
             }
             Keycode::R => {
                 println!("Rendering Specimens...");
-                self.population.render("tmp/", false, false);
+                self.simulation.population.render("tmp/", false, false);
             }
             Keycode::S => {
-                self.save_to_file();
+                self.simulation.save_to_file();
             }
             Keycode::W => {
                 println!("Parametric Exploitation.");
-                self.population.exploitation();
-            }
-            Keycode::PageDown => {
-                self.speed += SPEED_STEP;
-                println!("FPS = {}", self.speed);
+                self.simulation.population.exploitation();
             }
             Keycode::PageUp => {
-                if self.speed > SPEED_STEP {
-                    self.speed -= SPEED_STEP;
-                    println!("FPS = {}", self.speed);
+                if self.time_scale < MAX_TIME_SCALE {
+                    self.time_scale *= 2;
                 }
+                println!("Time scale = x{}", self.time_scale);
+            }
+            Keycode::PageDown => {
+                if self.time_scale > 1 {
+                    self.time_scale /= 2;
+                }
+                println!("Time scale = x{}", self.time_scale);
             }
             Keycode::N => {
-                self.speed = GAME_SPEED;
-                println!("Reset game speed to {}.", self.speed);
+                self.time_scale = 1;
+                self.fast_forward = false;
+                println!("Reset game speed to x{}.", self.time_scale);
+            }
+            Keycode::T => {
+                self.fast_forward = !self.fast_forward;
+                println!("Fast-forward = {}", self.fast_forward);
             }
             Keycode::Escape => ctx.quit().unwrap(),
             _ => (), // Do nothing
@@ -13,6 +13,14 @@ pub struct InputState {
     pub yaxis: f32,
     // FOV of the combined Sensor axis.
     pub fov_axis: f32,
+    // Strafe left and right axis.
+    pub strafe: f32,
+    // The left Sensors axis, on its own.
+    pub left_sensor_axis: f32,
+    // The right Sensors axis, on its own.
+    pub right_sensor_axis: f32,
+    // Slow down.
+    pub brake: f32,
     // Freeze all motor functions ;)
     pub freeze: f32,
     // Unleash the fire of hell upon your ennemy.
@@ -25,6 +33,10 @@ impl Default for InputState {
             xaxis: 0.0,
             yaxis: 0.0,
             fov_axis: 0.0,
+            strafe: 0.0,
+            left_sensor_axis: 0.0,
+            right_sensor_axis: 0.0,
+            brake: 0.0,
             freeze: 0.0,
             fire: false,
         }
@@ -6,6 +6,12 @@ extern crate rayon;
 extern crate fnv;
 extern crate glob;
 extern crate chrono;
+#[macro_use]
+extern crate clap;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate toml;
 #[macro_use(
     slog_o,
     slog_info,
@@ -27,12 +33,27 @@ extern crate slog_term;
 extern crate fluffy_penguin;
 
 mod actors;
+mod arena;
+mod cli;
+mod collisions;
+#[cfg(test)]
+mod collisions_reference;
 mod color_picker;
+mod config;
 mod gameboard;
 mod gameboard_controller;
+mod obstacles;
+mod simulation;
+mod sprites;
 
+use rand::Rng;
 use slog::Drain;
+use cli::{Command, Options};
+use config::SimConfig;
+use fluffy_penguin::genetic_algorithm::Population;
 use gameboard::State;
+use obstacles::Scenario;
+use simulation::Simulation;
 
 fn print_instructions() {
     println!();
@@ -53,7 +74,11 @@ fn print_instructions() {
     // );
     println!(
         "* {: <66} *",
-        "PageUp/PageDown to modify the simulation's speed."
+        "PageUp/PageDown to speed up/slow down the simulation."
+    );
+    println!(
+        "* {: <66} *",
+        "T to toggle fast-forward (as fast as possible, actors not drawn)."
     );
     println!("* {: <66} *", "N to reset the game's speed.");
     println!("* {: <66} *", "E to trigger the evolution process of the A.I. engine");
@@ -96,12 +121,111 @@ fn init_log() -> slog::Logger {
 
 
 fn main() {
+    let options = Options::from_args();
+
+    let _guard = slog_scope::set_global_logger(init_log());
+
+    if let Command::RenderBrains { ref save_file, ref output_dir } = options.command {
+        match Population::<f32>::load_from_file(save_file) {
+            Ok(population) => {
+                info!("Rendering Specimens from '{}' to '{}'...", save_file, output_dir);
+                population.render(output_dir, false, false);
+            },
+            Err(e) => {
+                crit!("Fail to load the population from '{}': {:?}", save_file, e);
+                ::std::process::exit(1);
+            },
+        }
+        return;
+    }
+
+    // Every random draw of the game derives from this seed, so a run can be reproduced.
+    let seed: u64 = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    info!("Run seed: {}", seed);
+    // The A.I. engine draws the brains and their mutations from its own unseeded generator.
+    warn!(
+        "The brains and their mutations are not driven by the run seed, a generation can only \
+         be replayed from its save file."
+    );
+
+    let sim_config = match SimConfig::from_file(&options.sim_conf)
+        .and_then(|sim_config| sim_config.validate_world(options.wrap_world).map(|_| sim_config))
+    {
+        Ok(sim_config) => sim_config,
+        Err(e) => {
+            crit!("Invalid simulation config: {}", e);
+            ::std::process::exit(1);
+        },
+    };
+    info!("Simulation config loaded from '{}':\n{}", options.sim_conf, sim_config.to_toml());
+
+    // The arena is empty unless a scenario is given.
+    let scenario = match options.scenario {
+        Some(ref scenario_file) => match Scenario::from_file(scenario_file) {
+            Ok(scenario) => {
+                info!(
+                    "Scenario loaded from '{}': {} obstacles.",
+                    scenario_file,
+                    scenario.obstacles.len()
+                );
+                scenario
+            },
+            Err(e) => {
+                crit!("Invalid scenario: {}", e);
+                ::std::process::exit(1);
+            },
+        },
+        None => Scenario::default(),
+    };
+
+    let mut simulation = Simulation::new(sim_config, &scenario, seed, options.actor_number)
+        .wrap_world(options.wrap_world)
+        .set_save_directory(&options.save_dir);
+
+    if let Some(ref load_file) = options.load_file {
+        match simulation.load_population_from_file(load_file) {
+            Ok(_) => info!("Loading Game from '{}'.", load_file),
+            Err(_) => {
+                crit!("Fail to load the game from '{}'.", load_file);
+                ::std::process::exit(1);
+            },
+        };
+        simulation.reset_board().expect("Fail to reset the Gameboard after loading a save file.");
+    } else {
+        // The first brains can't be drawn again from the seed, so they are saved right away to
+        // be able to replay the first generation.
+        simulation.save_to_file();
+    }
+    let mut simulation = simulation.with_generation_limit(options.generation_limit);
+
+    if options.command == Command::Train {
+        // Without any window to watch, the simulation is stepped as fast as possible until the
+        // generation limit is reached.
+        match options.generation_limit {
+            Some(limit) => info!("Running headless for {} generations.", limit),
+            None => info!("Running headless with no generation limit."),
+        };
+        match simulation.run_headless(gameboard::TICK_DT) {
+            Err(e) => {
+                crit!("Error encountered during simulation: {}", e);
+                ::std::process::exit(1);
+            },
+            Ok(_) => {
+                simulation.save_to_file();
+                info!("Simulation exited cleanly!");
+            },
+        }
+        return;
+    }
+
+    // The window is only configured when there is one, so the headless runs don't need its conf.
     let c = {
-        let mut f = ::std::fs::File::open("resources/conf.toml")
+        let mut f = ::std::fs::File::open(&options.window_conf)
             .expect("conf.toml not found.");
         ggez::conf::Conf::from_toml_file(&mut f)
             .expect("Failed to load Conf from toml file.")
     };
+
     let ctx =
         &mut ggez::Context::load_from_conf("ggez-generative-art", "awesome_person", c)
             .expect("Failed to buil Context.");
@@ -120,16 +244,12 @@ fn main() {
         //ctx.filesystem.read_config().expect("Fail to read config");
     }
 
-    let state = &mut State::new(ctx)
-        .expect("Fail to instantiate the game state.")
-        .with_actor_capacity(32)
-        .wrap_world(true);
+    let state = &mut State::new(ctx, simulation)
+        .expect("Fail to instantiate the game state.");
 
     println!("{:#?}", ctx.conf);
     print_instructions();
 
-    let _guard = slog_scope::set_global_logger(init_log());
-
     match ggez::event::run(ctx, state) {
         Err(e) => {
             crit!("Error encountered during game: {}", e);
@@ -0,0 +1,221 @@
+//! The static obstacles of the arena, loaded from a scenario file. They block the pandas, stop or
+//! reflect the bullets, and can be seen by the sensors, so the pandas can learn to use cover.
+
+use na;
+use ncollide2d::query::{PointQuery, Ray, RayCast, RayIntersection};
+use ncollide2d::shape::{Ball, Cuboid, Segment};
+use std::fs::File;
+use std::io::Read;
+use toml;
+
+/// The layout of the arena: its obstacles and how they treat the bullets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    // What happens to a bullet hitting an obstacle.
+    pub bullet_impact: BulletImpact,
+    // The obstacles standing in the arena.
+    pub obstacles: Vec<ObstacleShape>,
+}
+
+/// What happens to a bullet hitting an obstacle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BulletImpact {
+    // The bullet is stopped and removed from the gameboard.
+    Absorb,
+    // The bullet bounces off the obstacle.
+    Reflect,
+}
+
+impl Default for BulletImpact {
+    fn default() -> Self {
+        BulletImpact::Absorb
+    }
+}
+
+/// The shape of an obstacle, in world units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "lowercase")]
+pub enum ObstacleShape {
+    // A disc.
+    Circle { center: [f32; 2], radius: f32 },
+    // An axis aligned rectangle.
+    Box { center: [f32; 2], size: [f32; 2] },
+    // A thin wall going through each point in turn.
+    Polyline { points: Vec<[f32; 2]> },
+}
+
+impl Scenario {
+    /// Loads and validates a scenario from a toml file.
+    pub fn from_file(file_name: &str) -> Result<Self, String> {
+        let mut content = String::new();
+        File::open(file_name)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| format!("Fail to read '{}': {}", file_name, e))?;
+
+        let scenario: Scenario = toml::from_str(&content)
+            .map_err(|e| format!("Fail to parse '{}': {}", file_name, e))?;
+        scenario.validate()?;
+
+        Ok(scenario)
+    }
+
+    /// Checks that every obstacle makes sense before building anything with it.
+    pub fn validate(&self) -> Result<(), String> {
+        let is_finite = |point: &[f32; 2]| point[0].is_finite() && point[1].is_finite();
+
+        for (idx, obstacle) in self.obstacles.iter().enumerate() {
+            let is_valid = match *obstacle {
+                ObstacleShape::Circle { ref center, radius } => {
+                    is_finite(center) && radius > 0.0 && radius.is_finite()
+                },
+                ObstacleShape::Box { ref center, ref size } => {
+                    is_finite(center) && is_finite(size) && size[0] > 0.0 && size[1] > 0.0
+                },
+                ObstacleShape::Polyline { ref points } => {
+                    points.len() >= 2 && points.iter().all(|point| is_finite(point))
+                },
+            };
+
+            if !is_valid {
+                return Err(format!("Obstacle {} is invalid: {:?}.", idx, obstacle));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One convex piece of an obstacle, a polyline being made of several segments.
+#[derive(Debug, Clone)]
+enum Piece {
+    Ball(Ball<f32>),
+    Cuboid(Cuboid<f32>),
+    Segment(Segment<f32>),
+}
+
+/// All the obstacles of the arena, ready to be tested against.
+#[derive(Debug, Clone, Default)]
+pub struct Obstacles {
+    // What happens to a bullet hitting an obstacle.
+    pub bullet_impact: BulletImpact,
+    // The shapes of the obstacles, used to draw them.
+    shapes: Vec<ObstacleShape>,
+    // Every convex piece of the obstacles along with its isometry.
+    pieces: Vec<(na::Isometry2<f32>, Piece)>,
+}
+
+impl Obstacles {
+    /// Builds the obstacles of a scenario.
+    pub fn new(scenario: &Scenario) -> Self {
+        let mut pieces: Vec<(na::Isometry2<f32>, Piece)> = Vec::new();
+        let centered_at = |center: &[f32; 2]| {
+            na::Isometry2::new(na::Vector2::new(center[0], center[1]), na::zero())
+        };
+
+        for obstacle in &scenario.obstacles {
+            match *obstacle {
+                ObstacleShape::Circle { ref center, radius } => {
+                    pieces.push((centered_at(center), Piece::Ball(Ball::new(radius))));
+                },
+                ObstacleShape::Box { ref center, ref size } => {
+                    let half_extents = na::Vector2::new(size[0] / 2.0, size[1] / 2.0);
+                    pieces.push((centered_at(center), Piece::Cuboid(Cuboid::new(half_extents))));
+                },
+                ObstacleShape::Polyline { ref points } => {
+                    for pair in points.windows(2) {
+                        let segment = Segment::new(
+                            na::Point2::new(pair[0][0], pair[0][1]),
+                            na::Point2::new(pair[1][0], pair[1][1]),
+                        );
+                        pieces.push((na::Isometry2::identity(), Piece::Segment(segment)));
+                    }
+                },
+            }
+        }
+
+        Obstacles {
+            bullet_impact: scenario.bullet_impact,
+            shapes: scenario.obstacles.clone(),
+            pieces,
+        }
+    }
+
+    /// Tells us if there is no obstacle at all.
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// Returns the shapes of the obstacles.
+    pub fn shapes(&self) -> &[ObstacleShape] {
+        &self.shapes
+    }
+
+    /// Returns the closest intersection of a ray with the obstacles, if any. The time of impact
+    /// is expressed in lengths of the direction of the ray.
+    pub fn cast_ray(&self, ray: &Ray<f32>) -> Option<RayIntersection<f32>> {
+        let mut closest: Option<RayIntersection<f32>> = None;
+
+        for &(ref iso, ref piece) in &self.pieces {
+            let intersection = match *piece {
+                Piece::Ball(ref shape) => shape.toi_and_normal_with_ray(iso, ray, true),
+                Piece::Cuboid(ref shape) => shape.toi_and_normal_with_ray(iso, ray, true),
+                Piece::Segment(ref shape) => shape.toi_and_normal_with_ray(iso, ray, true),
+            };
+
+            if let Some(intersection) = intersection {
+                let is_closer = match closest {
+                    Some(ref closest) => intersection.toi < closest.toi,
+                    None => true,
+                };
+                if is_closer {
+                    closest = Some(intersection);
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Tells us if a disc overlaps any obstacle.
+    pub fn overlaps(&self, center: na::Point2<f32>, radius: f32) -> bool {
+        self.pieces.iter().any(|&(ref iso, ref piece)| {
+            let projection = match *piece {
+                Piece::Ball(ref shape) => shape.project_point(iso, &center, false),
+                Piece::Cuboid(ref shape) => shape.project_point(iso, &center, false),
+                Piece::Segment(ref shape) => shape.project_point(iso, &center, false),
+            };
+            projection.is_inside || (center - projection.point).norm() < radius
+        })
+    }
+
+    /// Returns the shift pushing a disc out of every obstacle it overlaps.
+    pub fn push_out(&self, center: na::Point2<f32>, radius: f32) -> na::Vector2<f32> {
+        let mut pushed = center;
+
+        for &(ref iso, ref piece) in &self.pieces {
+            let projection = match *piece {
+                Piece::Ball(ref shape) => shape.project_point(iso, &pushed, false),
+                Piece::Cuboid(ref shape) => shape.project_point(iso, &pushed, false),
+                Piece::Segment(ref shape) => shape.project_point(iso, &pushed, false),
+            };
+
+            // The projection lies on the border of the obstacle, we move the disc along the line
+            // going through it until the disc only touches the border.
+            let delta = pushed - projection.point;
+            let dist = delta.norm();
+            if dist == 0.0 {
+                continue;
+            }
+
+            if projection.is_inside {
+                pushed -= delta / dist * (dist + radius);
+            } else if dist < radius {
+                pushed += delta / dist * (radius - dist);
+            }
+        }
+
+        pushed - center
+    }
+}
@@ -0,0 +1,906 @@
+//! The simulation core: pandas, bullets, sensors, collisions and evolution.
+//! Nothing in here needs a graphics context, so it can run without any window (headless mode).
+
+use actors::{action_decoder, ActionDecoder, Body, BodyGrid, Bullet, Food, InputNormalizer, Panda};
+use arena::Arena;
+use collisions;
+use color_picker::*;
+use config::{
+    ActorConfig, ActuatorConfig, DecoderKind, FoodConfig, FriendlyFire, InputChannels,
+    ProprioceptionConfig, SensorConfig, SimConfig, TeamConfig,
+};
+use gameboard_controller::InputState;
+use ggez::GameResult;
+use obstacles::{Obstacles, Scenario};
+use rayon::prelude::*;
+use fnv::FnvHashMap;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs;
+use toml;
+
+use fluffy_penguin::genetic_algorithm::Specimen;
+use fluffy_penguin::genetic_algorithm::Population;
+
+const SAVE_DIR: &str = "tmp/save/";
+// Number of bullet per actor the gameboard makes room for.
+const BULLET_NUMBER_PER_ACTOR: usize = 4;
+// All the color a panda can wear.
+const COLOR_ARRAY: [[f32; 4]; 8] = [WHITE, AQUA, RED, GREEN, BLUE, ORANGE, PURPLE, YELLOW];
+
+/// What is saved along with a population, in a toml file next to it, so the population is loaded
+/// back the way it was trained.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SaveInfo {
+    // How the output of the brains is turned into orders.
+    decoder: DecoderKind,
+    // Are the inputs of the brains normalised.
+    normalize_inputs: bool,
+    // Does each sensor tell about the kin of what it sees.
+    kin_channels: bool,
+    // The optional orders the brains give, read by the decoder.
+    actuators: ActuatorConfig,
+    // What a panda feels about its own state.
+    proprioception: ProprioceptionConfig,
+    // The optional inputs that were in play, the ones of the config if none was saved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channels: Option<InputChannels>,
+    // The rig of sensors, the one of the config if none was saved. It must stay the last field,
+    // as the toml format wants the arrays of tables after the rest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensors: Option<Vec<SensorConfig>>,
+}
+
+impl SaveInfo {
+    /// Returns the name of the file saved along with a population.
+    fn file_name(population_file_name: &str) -> String {
+        format!("{}.toml", population_file_name)
+    }
+
+    /// Returns what was saved along with a population, the populations saved before there was
+    /// any such file being decoded by the differential decoder without normalised inputs nor
+    /// optional actuators, kin channels or proprioception.
+    fn load(population_file_name: &str) -> Self {
+        let file_name = SaveInfo::file_name(population_file_name);
+        match fs::read_to_string(&file_name) {
+            Ok(content) => match toml::from_str::<SaveInfo>(&content) {
+                Ok(info) => info,
+                Err(e) => {
+                    warn!("Fail to parse '{}': {}", file_name, e);
+                    SaveInfo::default()
+                },
+            },
+            Err(_) => SaveInfo::default(),
+        }
+    }
+
+    /// Overrides the config with what the population was saved with, warning about anything
+    /// that differs.
+    fn apply_to(self, config: &mut ActorConfig) {
+        if self.decoder != config.decoder {
+            warn!(
+                "The population was saved with the {:?} decoder, it's used instead of the {:?} \
+                 one of the config.",
+                self.decoder, config.decoder
+            );
+        }
+        config.decoder = self.decoder;
+
+        if self.normalize_inputs != config.normalize_inputs {
+            warn!(
+                "The population was saved with normalize_inputs = {}, it's used instead of the \
+                 config.",
+                self.normalize_inputs
+            );
+        }
+        config.normalize_inputs = self.normalize_inputs;
+
+        if self.actuators != config.actuators {
+            warn!(
+                "The population was saved with the actuators {:?}, they are used instead of the \
+                 ones of the config.",
+                self.actuators
+            );
+        }
+        config.actuators = self.actuators;
+
+        if self.kin_channels != config.kin_channels {
+            warn!(
+                "The population was saved with kin_channels = {}, it's used instead of the \
+                 config.",
+                self.kin_channels
+            );
+        }
+        config.kin_channels = self.kin_channels;
+
+        if self.proprioception != config.proprioception {
+            warn!(
+                "The population was saved with the proprioception {:?}, it's used instead of the \
+                 one of the config.",
+                self.proprioception
+            );
+        }
+        config.proprioception = self.proprioception;
+
+        if let Some(channels) = self.channels {
+            if channels != config.channels {
+                warn!(
+                    "The population was saved with the inputs {:?} in play, they are used \
+                     instead of the ones of the config and the scenario.",
+                    channels
+                );
+            }
+            config.channels = channels;
+        }
+
+        if let Some(sensors) = self.sensors {
+            if sensors != config.sensors {
+                warn!(
+                    "The population was saved with the sensor rig {:?}, it's used instead of the \
+                     one of the config.",
+                    sensors
+                );
+            }
+            config.sensors = sensors;
+        }
+    }
+}
+
+/// This is the state of the world, without anything related to how it's drawn.
+pub struct Simulation {
+    pub panda_vector: Vec<Panda>,
+    pub population: Population<f32>,
+    pub bullet_vector: Vec<Bullet>,
+    pub food_vector: Vec<Food>,
+    pub generation: usize,
+    pub countdown: usize,
+    // Every tunable value of the simulation, including the arena the pandas live in.
+    config: SimConfig,
+    // The static obstacles standing in the arena.
+    obstacles: Obstacles,
+    // Turns the output of the brains into orders to the pandas.
+    decoder: Box<dyn ActionDecoder>,
+    // Maps the inputs of the brains to their documented range, if they are normalised.
+    normalizer: Option<InputNormalizer>,
+    wrap_world: bool,
+    save_dir: String,
+    // The seed of the run, every random draw of the game is derived from it.
+    seed: u64,
+    // The random number generator of the current round.
+    rng: StdRng,
+    // The generation at which the simulation stops, if any.
+    last_generation: Option<usize>,
+}
+
+impl Simulation {
+    /// Returns a new simulation running with the given configuration, in the arena laid out by
+    /// the scenario, and number of pandas, with all its random draws derived from the seed.
+    pub fn new(mut config: SimConfig, scenario: &Scenario, seed: u64, actor_number: usize) -> Self {
+        use actors::{ai_engine_input_len, ai_engine_output_len};
+
+        // The inputs of the brains depend on the features in play, the obstacles included.
+        let obstacles = Obstacles::new(scenario);
+        config.actors.channels = config.input_channels(!obstacles.is_empty());
+
+        let mut rng = Simulation::round_rng(seed, 0);
+        let panda_vector = Simulation::new_actor_population(
+            actor_number,
+            &config.arena,
+            &obstacles,
+            &config.actors,
+            &config.teams,
+            &mut rng,
+        );
+
+        // There is one Specimen per Panda.
+        let population_size: usize = actor_number;
+        let input_size: usize = ai_engine_input_len(&config.actors);
+        let output_size: usize = ai_engine_output_len(&config.actors);
+        let mutation_probability: f32 = config.evolution.mutation_probability;
+        let mut population: Population<f32> = Population::new(
+            population_size,
+            input_size,
+            output_size,
+            mutation_probability,
+        );
+        population
+            // .set_lambda((actor_number / 2 ) as usize)
+            .set_s_rank(1.5);
+        population.exploration();
+
+        let bullet_vector: Vec<Bullet> =
+            Vec::with_capacity(actor_number * BULLET_NUMBER_PER_ACTOR);
+        let food_vector =
+            Simulation::new_food(&config.arena, &obstacles, &config.food, &mut rng);
+
+        Simulation {
+            panda_vector,
+            population,
+            bullet_vector,
+            food_vector,
+            generation: 0,
+            countdown: config.evolution.countdown,
+            decoder: action_decoder(config.actors.decoder),
+            normalizer: Simulation::new_normalizer(&config.actors),
+            config,
+            obstacles,
+            wrap_world: true,
+            save_dir: SAVE_DIR.to_string(),
+            seed,
+            rng,
+            last_generation: None,
+        }
+    }
+
+
+    /// Returns the random number generator of a round, so any generation can be re-simulated
+    /// from the seed of its run.
+    fn round_rng(seed: u64, generation: usize) -> StdRng {
+        StdRng::seed_from_u64(seed ^ (generation as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+
+    /// Returns the seed of the run.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+
+    /// Returns the size of the torus the world is wrapped around, if the world is wrapped.
+    fn torus_size(&self) -> Option<(f32, f32)> {
+        if self.wrap_world {
+            Some(self.config.arena.torus_size(self.config.actors.wrap_margin()))
+        } else {
+            None
+        }
+    }
+
+
+    /// Determines if the position of each Panda will be wrap in a toric world, or if they will be
+    /// stuck on the imaginary walls of the arena.
+    /// True by default.
+    pub fn wrap_world(mut self, b: bool) -> Self {
+        self.wrap_world = b;
+        self
+    }
+
+
+    /// Stops the simulation after the given number of generations from now.
+    pub fn with_generation_limit(mut self, generation_limit: Option<usize>) -> Self {
+        self.last_generation = generation_limit.map(|limit| self.generation + limit);
+        self
+    }
+
+
+    /// Tells us if the simulation has reached its generation limit.
+    pub fn is_done(&self) -> bool {
+        match self.last_generation {
+            Some(last_generation) => self.generation >= last_generation,
+            None => false,
+        }
+    }
+
+
+    /// Returns the arena the simulation is running in.
+    pub fn arena(&self) -> &Arena {
+        &self.config.arena
+    }
+
+
+    /// Returns the obstacles standing in the arena.
+    pub fn obstacles(&self) -> &Obstacles {
+        &self.obstacles
+    }
+
+
+    /// Update the default game save directory.
+    pub fn set_save_directory(mut self, save_dir: &str) -> Self {
+        self.save_dir = save_dir.to_string();
+        self
+    }
+
+
+    /// Reset the population of Panda on the gameboard.
+    fn new_actor_population(
+        actor_size: usize,
+        arena: &Arena,
+        obstacles: &Obstacles,
+        config: &ActorConfig,
+        teams: &TeamConfig,
+        rng: &mut StdRng,
+    ) -> Vec<Panda> {
+        // Create a iterator from which we can cycle through to give our pandas roughfly different
+        // colors.
+        let mut color_iter_cycle = COLOR_ARRAY.iter().cycle();
+
+        let mut panda_vector: Vec<Panda> = Vec::with_capacity(actor_size);
+        for tag in 0..actor_size {
+            let panda_color = color_iter_cycle
+                .next()
+                .expect("Fail to cycle through the available color.");
+            // In the team mode, the pandas wear the color of their team.
+            let team = teams.team_of(tag);
+            let panda_color = match team {
+                Some(team) => &COLOR_ARRAY[team % COLOR_ARRAY.len()],
+                None => panda_color,
+            };
+            let mut panda =
+                Panda::new(tag as usize, *panda_color, arena, obstacles, config, rng);
+            panda.set_team(team);
+            panda_vector.push(panda);
+        }
+        panda_vector
+    }
+
+    /// Spread the food all over the arena, around the obstacles.
+    fn new_food(
+        arena: &Arena,
+        obstacles: &Obstacles,
+        config: &FoodConfig,
+        rng: &mut StdRng,
+    ) -> Vec<Food> {
+        (0..config.count)
+            .map(|_| Food::new(arena, obstacles, config, rng))
+            .collect()
+    }
+
+    /// This is where the pandas eat the food they touch, which then grows back elsewhere.
+    fn handle_food(&mut self) {
+        if self.food_vector.is_empty() {
+            return;
+        }
+
+        let pickups =
+            collisions::food_pickups(&self.panda_vector, &self.food_vector, self.torus_size());
+
+        for (panda_idx, food_idx) in pickups {
+            let panda = &mut self.panda_vector[panda_idx];
+            panda.score += self.config.food.points;
+            panda.heal(self.config.food.health);
+
+            self.food_vector[food_idx].respawn(
+                &self.config.arena,
+                &self.obstacles,
+                &mut self.rng,
+            );
+        }
+    }
+
+    /// This is where the collision between the pandas and the bullets are handled.
+    fn handle_collisions(&mut self) -> GameResult<()> {
+        // This HashMap let us update the score of panda that successfully shoot someone.
+        let mut successfull_panda_shot_hashmap: FnvHashMap<usize, f32> =
+            FnvHashMap::with_capacity_and_hasher(self.panda_vector.len(), Default::default());
+
+        // Only the pairs close enough to each other are tested, and the hits come in the order
+        // they happened.
+        let hits = collisions::broad_phase_hits(
+            &self.panda_vector,
+            &self.bullet_vector,
+            self.torus_size(),
+            self.config.actors.wrap_margin(),
+        );
+
+        // The bullets stopped in a panda during this tick. They can't be told by their removal
+        // flag, which is also set when they ran into an obstacle or out of range after the hit.
+        let mut stopped_bullets: Vec<bool> = vec![false; self.bullet_vector.len()];
+
+        for hit in hits {
+            let panda = &mut self.panda_vector[hit.panda];
+            let bullet = &mut self.bullet_vector[hit.bullet];
+
+            // A bullet stops in the first panda it hits, and a panda killed earlier in the tick
+            // can't be hit anymore.
+            if stopped_bullets[hit.bullet] || !panda.is_alive() {
+                continue;
+            }
+
+            // In the team mode, the bullets may go through the allies, or cost their shooter.
+            let is_friendly_fire = panda.team.is_some() && panda.team == bullet.body.team;
+            let shooter_points = if is_friendly_fire {
+                match self.config.teams.friendly_fire {
+                    FriendlyFire::Off => continue,
+                    FriendlyFire::Penalised => -self.config.teams.friendly_fire_penalty,
+                    FriendlyFire::Allowed => self.config.evolution.point_win_per_successful_shot,
+                }
+            } else {
+                self.config.evolution.point_win_per_successful_shot
+            };
+
+            panda.score -= self.config.evolution.point_lost_when_shot;
+            stopped_bullets[hit.bullet] = true;
+            bullet.to_remove = true;
+
+            if panda.take_damage(self.config.actors.bullet_damage) {
+                panda.respawn_countdown = self.config.actors.respawn_delay.unwrap_or(0.0);
+            }
+
+            let score = successfull_panda_shot_hashmap
+                .entry(bullet.tag)
+                .or_insert(0.0);
+            *score += shooter_points;
+        }
+
+        // Here we update the score of each Panda whose bullet hit a target.
+        for (tag, score) in &successfull_panda_shot_hashmap {
+            self.panda_vector[*tag].score += score;
+        }
+
+        Ok(())
+    }
+
+
+    /// This is where the dead pandas wait for their respawn, if they are allowed to come back to
+    /// life during the round.
+    fn handle_respawns(&mut self, dt: f32) {
+        if self.config.actors.respawn_delay.is_none() {
+            return;
+        }
+
+        for panda in &mut self.panda_vector {
+            if !panda.is_alive() {
+                panda.respawn_countdown -= dt;
+                if panda.respawn_countdown <= 0.0 {
+                    panda.respawn(&self.config.arena, &self.obstacles, &mut self.rng);
+                }
+            }
+        }
+    }
+
+
+    /// This is where the pandas bumping into each other are pushed apart, if the body collisions
+    /// are on.
+    fn handle_panda_contacts(&mut self) {
+        if !self.config.actors.body_collisions {
+            return;
+        }
+
+        let contacts = collisions::panda_contacts(
+            &self.panda_vector,
+            self.torus_size(),
+            self.config.actors.wrap_margin(),
+        );
+        let elasticity = self.config.actors.collision_elasticity;
+        let point_per_contact_tick = self.config.evolution.point_per_contact_tick;
+
+        for contact in contacts {
+            let normal = contact.normal;
+            let relative_velocity = self.panda_vector[contact.second].velocity()
+                - self.panda_vector[contact.first].velocity();
+            let closing_speed = -relative_velocity.dot(&normal);
+
+            // All the pandas weigh the same, so they share the impulse and the push equally. Pandas
+            // already moving apart are only pushed apart.
+            let impulse = if closing_speed > 0.0 {
+                (1.0 + elasticity) * closing_speed / 2.0
+            } else {
+                0.0
+            };
+            let shift = normal * (contact.penetration / 2.0);
+
+            let obstacles = &self.obstacles;
+            let arena = &self.config.arena;
+            {
+                let first = &mut self.panda_vector[contact.first];
+                first.bump(-shift, -normal * impulse, obstacles, self.wrap_world, arena);
+                first.score += point_per_contact_tick;
+            }
+            {
+                let second = &mut self.panda_vector[contact.second];
+                second.bump(shift, normal * impulse, obstacles, self.wrap_world, arena);
+                second.score += point_per_contact_tick;
+            }
+        }
+    }
+
+
+    /// Runs one tick of the simulation: moves the bullets, lets each Panda sense and act
+    /// according to its brain, handles the collisions, and triggers the evolution process when
+    /// the countdown runs out.
+    pub fn step(&mut self, dt: f32) -> GameResult<()> {
+        // Here we clean the gameboard from all unnecessary bullet.
+        let mut bullet_to_keep_vector: Vec<Bullet> =
+            Vec::with_capacity(self.bullet_vector.len());
+
+        for bullet in &mut self.bullet_vector {
+            if !bullet.to_remove {
+                bullet.update(&self.config.arena, &self.obstacles, &self.config.actors, dt)?;
+                bullet_to_keep_vector.push(bullet.to_owned());
+            }
+        }
+        self.bullet_vector = bullet_to_keep_vector;
+        {
+            // Here we build a vector containing all the object each panda can interact with: the
+            // other pandas, the bullets and the food.
+            let cap: usize =
+                self.panda_vector.len() + self.bullet_vector.len() + self.food_vector.len();
+            let mut body_vector: Vec<Body> = Vec::with_capacity(cap);
+            // The dead pandas are out of the game until they respawn.
+            for panda in self.panda_vector.iter().filter(|panda| panda.is_alive()) {
+                body_vector.push(panda.body.clone());
+            }
+
+            for bullet in &self.bullet_vector {
+                body_vector.push(bullet.body.clone());
+            }
+
+            for food in &self.food_vector {
+                body_vector.push(food.body.clone());
+            }
+
+            // The grid narrows the ray casting of each sensor down to the bodies along the ray.
+            // In a toroidal world, the bodies near the seams get ghosts on the other side so
+            // they can be seen across them.
+            let grid = match self.torus_size() {
+                Some(size) => {
+                    // A body is seen as soon as its edge crosses a ray, so the reach goes as far
+                    // as the largest body in the grid, be it a panda, a bullet or some food.
+                    let max_radius = body_vector
+                        .iter()
+                        .map(|body| body.nshape.radius())
+                        .fold(0.0, f32::max);
+                    let actors = &self.config.actors;
+                    let reach = actors.max_sensor_range() + max_radius + actors.wrap_margin();
+                    BodyGrid::new_wrapped(body_vector, size, reach)
+                },
+                None => BodyGrid::new(body_vector),
+            };
+
+            // Let's update all the pandas.
+            let mut normalized_input: Vec<f32> = Vec::new();
+            for i in 0..self.panda_vector.len() {
+                let panda: &Panda = &self.panda_vector[i];
+                let specimen: &mut Specimen<f32> = &mut self.population.species[i];
+
+                // We manually update the input values we feed to the ANN.
+                match self.normalizer {
+                    Some(ref normalizer) => {
+                        normalizer.normalize(&panda.input_to_ai, &mut normalized_input);
+                        specimen.update_input(&normalized_input);
+                    },
+                    None => specimen.update_input(&panda.input_to_ai),
+                }
+            }
+
+
+            // Here we evaluate each specimen in parallel.
+            let mut input_state_v: Vec<InputState> = Vec::with_capacity(self.panda_vector.len());
+            let actor_config = &self.config.actors;
+            let decoder = &self.decoder;
+            self.population.species.par_iter_mut()
+                .map(|specimen| {
+                    // Input commands computed by the ANN from the A.I. engine.
+                    decoder.decode(&specimen.evaluate(), actor_config)
+                }).collect_into_vec(&mut input_state_v);
+
+
+            // Let's update all the pandas in parallel: each one moves and senses on its own, and
+            // the bullets they fire are merged afterwards in the order of the pandas so the
+            // results stay deterministic.
+            let wrap_world = self.wrap_world;
+            let arena = &self.config.arena;
+            let obstacles = &self.obstacles;
+            let actor_config = &self.config.actors;
+            let grid = &grid;
+            let mut fired_bullet_v: Vec<GameResult<Option<Bullet>>> =
+                Vec::with_capacity(self.panda_vector.len());
+            self.panda_vector.par_iter_mut()
+                .zip(input_state_v.par_iter())
+                .map(|(panda, input_state)| {
+                    if !panda.is_alive() {
+                        return Ok(None);
+                    }
+
+                    // Input commands computed by the ANN from the A.I. engine.
+                    let bullet = panda.handle_input(input_state, actor_config, dt);
+                    panda
+                        .update(grid, obstacles, wrap_world, arena, actor_config, dt)
+                        .map(|_| bullet)
+                }).collect_into_vec(&mut fired_bullet_v);
+
+            for fired_bullet in fired_bullet_v {
+                if let Some(bullet) = fired_bullet? {
+                    self.bullet_vector.push(bullet);
+                }
+            }
+        }
+
+        self.handle_panda_contacts();
+
+        {
+            self.handle_collisions()?;
+        }
+        self.handle_food();
+        self.handle_respawns(dt);
+
+        // Run the countdown before next auto evolution triggers.
+        if self.countdown == 0 {
+            self.evolve()?;
+            self.countdown = self.config.evolution.countdown;
+        } else {
+            self.countdown -= 1;
+        }
+
+        Ok(())
+    }
+
+
+    /// Steps the world without any rendering until the generation limit is reached, or forever
+    /// if there is none.
+    pub fn run_headless(&mut self, dt: f32) -> GameResult<()> {
+        while !self.is_done() {
+            self.step(dt)?;
+        }
+        info!("Reached generation {}, stopping the simulation.", self.generation);
+
+        Ok(())
+    }
+
+
+    /// Update the population from the A.I. engine.
+    pub fn evolve(&mut self) -> GameResult<()> {
+        // Let's keep track of how far we can get.
+        self.generation += 1;
+
+        // Update the fitness value of each Specimen with the score of its associated Panda, and
+        // how long it survived.
+        let point_per_second_alive = self.config.evolution.point_per_second_alive;
+        let fitness_vector: Vec<f32> = self
+            .panda_vector
+            .iter()
+            .map(|panda| panda.score + panda.time_alive * point_per_second_alive)
+            .collect();
+
+        // In the team mode, part of the fitness comes from the average fitness of the team.
+        let share = self.config.teams.team_fitness_share;
+        let mut team_fitness: FnvHashMap<usize, (f32, usize)> = FnvHashMap::default();
+        for (panda, fitness) in self.panda_vector.iter().zip(&fitness_vector) {
+            if let Some(team) = panda.team {
+                let entry = team_fitness.entry(team).or_insert((0.0, 0));
+                entry.0 += fitness;
+                entry.1 += 1;
+            }
+        }
+
+        for ((panda, fitness), specimen) in self
+            .panda_vector
+            .iter()
+            .zip(&fitness_vector)
+            .zip(&mut self.population.species)
+        {
+            specimen.fitness = match panda.team.and_then(|team| team_fitness.get(&team)) {
+                Some(&(sum, count)) => (1.0 - share) * fitness + share * sum / count as f32,
+                None => *fitness,
+            };
+        }
+
+        // Evolve the population by mating them together.
+        self.population.evolve();
+
+        // self.population.render(&format!("tmp/vizualisation/gen_{:0>3}/", self.generation), false, false);
+
+        if self.generation % self.config.evolution.exploration_tick == 0 {
+            info!("Generation {:>3} : Structural Exploration.", self.generation);
+            self.population.exploration();
+
+            info!("Rendering Specimens...");
+            self.population.render("tmp/vizualisation/", false, false);
+            self.save_to_file();
+        } else {
+            info!("Generation {:>3} : Parametric Exploitation.", self.generation);
+            self.population.exploitation();
+        }
+
+        self.reset_board()?;
+        Ok(())
+    }
+
+
+    /// Save the Panda's brains to file.
+    pub fn save_to_file(&self) {
+        use chrono::prelude::*;
+
+        let date = Local::now().format("%FT%Hh%Mm%Ss");
+        let file_name = format!(
+            "{}/{}_Population-gen{:03}-seed{}.bc",
+            self.save_dir, date, self.generation, self.seed
+        );
+        match self.population.save_to_file(&file_name) {
+            Ok(_) => info!("Saving Population to '{}'.", file_name),
+            Err(e) => warn!("Fail to save to '{}': {}", file_name, e),
+        };
+
+        let info_file_name = SaveInfo::file_name(&file_name);
+        let info = SaveInfo {
+            decoder: self.config.actors.decoder,
+            normalize_inputs: self.config.actors.normalize_inputs,
+            kin_channels: self.config.actors.kin_channels,
+            actuators: self.config.actors.actuators.clone(),
+            proprioception: self.config.actors.proprioception.clone(),
+            channels: Some(self.config.actors.channels),
+            sensors: Some(self.config.actors.sensors.clone()),
+        };
+        let saved = toml::to_string(&info)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&info_file_name, content).map_err(|e| e.to_string()));
+        if let Err(e) = saved {
+            warn!("Fail to save to '{}': {}", info_file_name, e);
+        }
+    }
+
+
+    /// Load the Panda's brains from file, along with what they were saved with. Nothing changes
+    /// if the brains don't fit the pandas.
+    pub fn load_population_from_file(&mut self, file_name: &str) -> Result<(), ()> {
+        use actors::{ai_engine_input_len, ai_engine_output_len};
+
+        let population: Population<f32> = match Population::load_from_file(file_name) {
+            Ok(population) => population,
+            Err(e) => {
+                crit!("{}", &format!("{:?}", e));
+                return Err(());
+            },
+        };
+
+        let mut config = self.config.actors.clone();
+        SaveInfo::load(file_name).apply_to(&mut config);
+
+        // The brains must be fed and read exactly the way they were trained.
+        let input_len = ai_engine_input_len(&config);
+        let output_len = ai_engine_output_len(&config);
+        let misfit = population.species.iter().find(|specimen| {
+            specimen.input_size != input_len || specimen.output_size != output_len
+        });
+        if let Some(specimen) = misfit {
+            crit!(
+                "The brains of '{}' have {} inputs and {} outputs, but the pandas have {} inputs \
+                 and {} outputs with this config.",
+                file_name,
+                specimen.input_size,
+                specimen.output_size,
+                input_len,
+                output_len
+            );
+            return Err(());
+        }
+
+        self.generation = population.generation_counter;
+        self.population = population;
+        self.decoder = action_decoder(config.decoder);
+        self.normalizer = Simulation::new_normalizer(&config);
+        self.config.actors = config;
+        Ok(())
+    }
+
+    /// Returns the normaliser of the inputs of the brains, if they are normalised.
+    fn new_normalizer(config: &ActorConfig) -> Option<InputNormalizer> {
+        if config.normalize_inputs {
+            Some(InputNormalizer::new(config))
+        } else {
+            None
+        }
+    }
+
+
+    /// Load the Panda's brains from the last previous save file.
+    pub fn reload_population_from_last_saved_game(&mut self) {
+        use glob::glob;
+
+        let wild_card = &format!("{}/*.bc", self.save_dir);
+        let mut fpl: Vec<String> = glob(wild_card).expect("Failed to read glob pattern")
+            .filter_map(|p| Some(p.unwrap().to_str().unwrap().to_string()))
+            .collect::<Vec<String>>();
+        fpl.sort();
+        debug!("fpl = {:#?}", fpl);
+
+        let file_name = fpl.last().unwrap().to_owned();
+        match self.load_population_from_file(&file_name) {
+            Ok(_) => info!("Loading Game from '{}'.", file_name),
+            Err(_) => warn!("Fail to load the game from '{}'.", file_name),
+        };
+    }
+
+
+    /// Wipe clean the entire gameboard.
+    pub fn reset_board(&mut self) -> GameResult<()> {
+        self.rng = Simulation::round_rng(self.seed, self.generation);
+        // There is one Panda per Specimen, even if we just loaded a population of another size.
+        self.panda_vector = Simulation::new_actor_population(
+            self.population.species.len(),
+            &self.config.arena,
+            &self.obstacles,
+            &self.config.actors,
+            &self.config.teams,
+            &mut self.rng,
+        );
+
+        // Clean all the bullets as well.
+        self.bullet_vector.clear();
+        self.food_vector = Simulation::new_food(
+            &self.config.arena,
+            &self.obstacles,
+            &self.config.food,
+            &mut self.rng,
+        );
+
+        self.countdown = self.config.evolution.countdown;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::graphics::Point2;
+    use obstacles::{BulletImpact, ObstacleShape};
+    use std::f32::consts::FRAC_PI_2;
+
+    // Long enough for a bullet to go through a panda in a single tick.
+    const DT: f32 = 0.1;
+
+    /// Returns a simulation of 2 pandas where a bullet of the second panda flew through the first
+    /// one during the last tick, with a wall standing right behind the first one if `with_wall`.
+    fn fire_through_first_panda(config: SimConfig, with_wall: bool) -> Simulation {
+        let mut simulation = Simulation::new(config, &Scenario::default(), 0, 2);
+        let target = simulation.panda_vector[0].pos;
+
+        if with_wall {
+            let scenario = Scenario {
+                bullet_impact: BulletImpact::Absorb,
+                obstacles: vec![ObstacleShape::Box {
+                    center: [target.x + 20.0, target.y],
+                    size: [10.0, 50.0],
+                }],
+            };
+            simulation.obstacles = Obstacles::new(&scenario);
+        }
+
+        // The bullet goes along the x axis, see `vec_from_angle`.
+        let start = Point2::new(target.x - 30.0, target.y);
+        let mut bullet = Bullet::new(1, None, start, FRAC_PI_2, &WHITE, &simulation.config.actors);
+        bullet
+            .update(
+                &simulation.config.arena,
+                &simulation.obstacles,
+                &simulation.config.actors,
+                DT,
+            )
+            .expect("Fail to update a bullet.");
+        simulation.bullet_vector.push(bullet);
+
+        simulation
+    }
+
+    /// Checks that the bullet was removed at the end of the tick, and that the first panda was
+    /// shot by it once the collisions are handled.
+    fn assert_first_panda_shot(mut simulation: Simulation) {
+        assert!(simulation.bullet_vector[0].to_remove);
+
+        simulation.handle_collisions().expect("Fail to handle the collisions.");
+        assert_eq!(
+            simulation.panda_vector[0].score,
+            -simulation.config.evolution.point_lost_when_shot
+        );
+        assert_eq!(
+            simulation.panda_vector[1].score,
+            simulation.config.evolution.point_win_per_successful_shot
+        );
+    }
+
+    #[test]
+    fn a_bullet_absorbed_by_a_wall_hits_the_panda_in_front_of_it() {
+        let simulation = fire_through_first_panda(SimConfig::default(), true);
+        assert_first_panda_shot(simulation);
+    }
+
+    #[test]
+    fn a_bullet_expiring_on_its_last_tick_hits_the_panda_on_its_way() {
+        let mut config = SimConfig::default();
+        config.actors.bullet_max_range = Some(40.0);
+
+        let simulation = fire_through_first_panda(config, false);
+        assert_first_panda_shot(simulation);
+    }
+}
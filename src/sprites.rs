@@ -0,0 +1,86 @@
+//! The images of our actors are built only once, and every actor of a frame is queued in a sprite
+//! batch so the whole gameboard is drawn in a handful of draw calls.
+
+use ggez::graphics::{self, Color, DrawParam, Image, Point2};
+use ggez::graphics::spritebatch::SpriteBatch;
+use ggez::{Context, GameResult};
+
+// The size in pixels of the disc image used to draw the bodies, the eyes and the bullets.
+pub const DISC_IMAGE_SIZE: u16 = 32;
+
+/// Returns the scale to apply to the disc image so it's drawn with the given radii.
+pub fn disc_scale(radius_x: f32, radius_y: f32) -> Point2 {
+    let size = f32::from(DISC_IMAGE_SIZE);
+    Point2::new(radius_x * 2.0 / size, radius_y * 2.0 / size)
+}
+
+/// Returns a white disc with an anti-aliased edge, which will be tinted by each sprite.
+fn disc_image(ctx: &mut Context) -> GameResult<Image> {
+    let size = DISC_IMAGE_SIZE as usize;
+    let radius = size as f32 / 2.0;
+
+    let mut rgba: Vec<u8> = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 + 0.5 - radius;
+            let dy = y as f32 + 0.5 - radius;
+            let alpha = (radius - (dx * dx + dy * dy).sqrt()).max(0.0).min(1.0);
+            rgba.extend_from_slice(&[255, 255, 255, (alpha * 255.0) as u8]);
+        }
+    }
+
+    Image::from_rgba8(ctx, DISC_IMAGE_SIZE, DISC_IMAGE_SIZE, &rgba)
+}
+
+/// All the sprite batches of the actors, drawn in this order: food, sensors, bodies, eyes and
+/// bullets.
+pub struct ActorBatches {
+    // The food lying around.
+    pub food: SpriteBatch,
+    // The rays of the sensors, drawn from a 1x1 white pixel.
+    pub sensors: SpriteBatch,
+    // The bodies of the pandas.
+    pub bodies: SpriteBatch,
+    // The 'eyes' of the pandas, drawn over their bodies.
+    pub eyes: SpriteBatch,
+    // The bullets flying around.
+    pub bullets: SpriteBatch,
+}
+
+impl ActorBatches {
+    /// Builds the images of our actors once and for all.
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let pixel = Image::solid(ctx, 1, Color::new(1.0, 1.0, 1.0, 1.0))?;
+        let disc = disc_image(ctx)?;
+
+        Ok(ActorBatches {
+            food: SpriteBatch::new(disc.clone()),
+            sensors: SpriteBatch::new(pixel),
+            bodies: SpriteBatch::new(disc.clone()),
+            eyes: SpriteBatch::new(disc.clone()),
+            bullets: SpriteBatch::new(disc),
+        })
+    }
+
+    /// Removes every sprite queued during the last frame.
+    pub fn clear(&mut self) {
+        self.food.clear();
+        self.sensors.clear();
+        self.bodies.clear();
+        self.eyes.clear();
+        self.bullets.clear();
+    }
+
+    /// Draws all the sprites queued for this frame.
+    pub fn draw(&self, ctx: &mut Context) -> GameResult<()> {
+        graphics::draw_ex(ctx, &self.food, DrawParam::default())?;
+        // We draw the sensors before the body of a Panda to avoid having the sensors's
+        // strait lines cutting the their bodies.
+        graphics::draw_ex(ctx, &self.sensors, DrawParam::default())?;
+        graphics::draw_ex(ctx, &self.bodies, DrawParam::default())?;
+        graphics::draw_ex(ctx, &self.eyes, DrawParam::default())?;
+        graphics::draw_ex(ctx, &self.bullets, DrawParam::default())?;
+
+        Ok(())
+    }
+}